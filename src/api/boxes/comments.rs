@@ -0,0 +1,238 @@
+//! Box comments
+//!
+//! Comments are the notes the users of a Pipeline leave on a Box, shown in
+//! its timeline.
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde_json::Value;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{BoxKey, CommentKey, Deleted, Model, PipelineKey, Timestamp, UserKey};
+use api::model;
+use super::User;
+
+/// A comment on a Box.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub comment_key: CommentKey,
+    pub box_key: BoxKey,
+    pub pipeline_key: Option<PipelineKey>,
+    pub creator_key: UserKey,
+    /// The user who wrote the comment.
+    #[serde(rename = "creatorSharingEntry", default)]
+    pub author: User,
+    pub timestamp: Timestamp,
+    pub message: String,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for Comment {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[
+            ("creatorSharingEntry", &self.author),
+        ])
+    }
+}
+
+/// The endpoint listing the comments on a Box.
+#[derive(Debug, Clone)]
+pub struct List {
+    pub box_key: BoxKey,
+}
+
+impl List {
+    pub fn new(box_key: &BoxKey) -> List {
+        List {
+            box_key: box_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for List {
+    type Response = Vec<Comment>;
+
+    fn path(&self) -> String {
+        format!("boxes/{}/comments", self.box_key)
+    }
+}
+
+/// The endpoint leaving a comment on a Box.
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    #[serde(skip)]
+    pub box_key: BoxKey,
+    pub message: String,
+}
+
+impl Create {
+    pub fn new(box_key: &BoxKey, message: &str) -> Create {
+        Create {
+            box_key: box_key.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Endpoint for Create {
+    type Response = Comment;
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn path(&self) -> String {
+        format!("boxes/{}/comments", self.box_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::form(self)?))
+    }
+}
+
+/// The endpoint deleting a comment on a Box.
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub box_key: BoxKey,
+    pub comment_key: CommentKey,
+}
+
+impl Delete {
+    pub fn new(box_key: &BoxKey, comment_key: &CommentKey) -> Delete {
+        Delete {
+            box_key: box_key.clone(),
+            comment_key: comment_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Delete {
+    type Response = Deleted;
+
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+
+    fn path(&self) -> String {
+        format!("boxes/{}/comments/{}", self.box_key, self.comment_key)
+    }
+}
+
+/// List the comments on a Box, oldest first
+///
+/// API docs:
+/// <https://www.streak.com/api/#listcomments>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::boxes::{self, comments};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     comments::create(&client, &acme.box_key, "Sent the quote").expect("comment on the box");
+///     comments::create(&client, &acme.box_key, "Quote approved").expect("comment on the box");
+///
+///     let comments = comments::list(&client, &acme.box_key).expect("list the comments");
+///     let messages: Vec<_> = comments.iter().map(|c| c.message.as_str()).collect();
+///     assert_eq!(messages, vec!["Sent the quote", "Quote approved"]);
+///
+///     let acme = boxes::get(&client, &acme.box_key).expect("get the box");
+///     assert_eq!(acme.comment_count, 2);
+///     assert_eq!(acme.last_comment_timestamp, Some(comments[1].timestamp));
+/// }
+/// ```
+pub fn list(client: &Client, box_key: &BoxKey) -> Result<Vec<Comment>, StreakError> {
+    client.execute(&List::new(box_key))
+}
+
+/// List the comments on a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn list_async(client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<Vec<Comment>> {
+    client.execute(&List::new(box_key))
+}
+
+/// Leave a comment on a Box, as the user of the API key
+///
+/// API docs:
+/// <https://www.streak.com/api/#createcomment>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::boxes::comments;
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     let comment = comments::create(&client, &acme.box_key, "Discount approved by the deal desk")
+///         .expect("comment on the box");
+///     assert_eq!(comment.box_key, acme.box_key);
+///     assert_eq!(comment.message, "Discount approved by the deal desk");
+///     assert_eq!(comment.author.user_key, comment.creator_key);
+/// }
+/// ```
+pub fn create(client: &Client, box_key: &BoxKey, message: &str) -> Result<Comment, StreakError> {
+    client.execute(&Create::new(box_key, message))
+}
+
+/// Leave a comment on a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn create_async(client: &AsyncClient, box_key: &BoxKey, message: &str) -> StreakFuture<Comment> {
+    client.execute(&Create::new(box_key, message))
+}
+
+/// Delete a comment on a Box
+///
+/// API docs:
+/// <https://www.streak.com/api/#deletecomment>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::boxes::comments;
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     let comment = comments::create(&client, &acme.box_key, "Wrong box").expect("comment on the box");
+///     comments::delete(&client, &acme.box_key, &comment.comment_key).expect("delete the comment");
+///     assert!(comments::list(&client, &acme.box_key).expect("list the comments").is_empty());
+///     assert!(comments::delete(&client, &acme.box_key, &comment.comment_key).is_err());
+/// }
+/// ```
+pub fn delete(client: &Client, box_key: &BoxKey, comment_key: &CommentKey) -> Result<(), StreakError> {
+    let delete = Delete::new(box_key, comment_key);
+    client.execute(&delete)?.check(delete.path())
+}
+
+/// Delete a comment on a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn delete_async(client: &AsyncClient, box_key: &BoxKey, comment_key: &CommentKey) -> StreakFuture<()> {
+    let delete = Delete::new(box_key, comment_key);
+    let resource = delete.path();
+    Box::new(client.execute(&delete).and_then(move |deleted| deleted.check(resource)))
+}
@@ -0,0 +1,84 @@
+//! Box actions
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{PipelineKey, StageKey};
+use super::Box;
+
+/// The endpoint creating a Box in a Pipeline.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Create {
+    #[serde(skip)]
+    pub pipeline_key: PipelineKey,
+    pub name: String,
+    pub stage_key: StageKey,
+}
+
+impl Create {
+    pub fn new(pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> Create {
+        Create {
+            pipeline_key: pipeline_key.clone(),
+            name: name.into(),
+            stage_key: stage_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Create {
+    type Response = Box;
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/boxes", self.pipeline_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::form(self)?))
+    }
+}
+
+/// Create a Box named `name` in the Stage `stage_key` of a Pipeline
+///
+/// Set its notes, assignees and everything else with `boxes::update`.
+///
+/// API docs:
+/// <https://www.streak.com/api/#createbox>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let lead = &pipeline.stage_order[0];
+///     let acme = streak::api::boxes::create(&client, &pipeline.pipeline_key, "ACME", lead)
+///         .expect("create a box");
+///     assert_eq!(acme.name, "ACME");
+///     assert_eq!(acme.stage_key, *lead);
+///
+///     let boxes = streak::api::boxes::list(&client, &pipeline.pipeline_key).expect("list boxes");
+///     assert_eq!(boxes[0].box_key, acme.box_key);
+/// }
+/// ```
+pub fn create(client: &Client, pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> Result<Box, StreakError> {
+    client.execute(&Create::new(pipeline_key, name, stage_key))
+}
+
+/// Create a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn create_async(client: &AsyncClient, pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> StreakFuture<Box> {
+    client.execute(&Create::new(pipeline_key, name, stage_key))
+}
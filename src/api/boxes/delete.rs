@@ -0,0 +1,72 @@
+//! Box actions
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::BoxKey;
+use api::Deleted;
+
+/// The endpoint deleting a Box.
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub box_key: BoxKey,
+}
+
+impl Delete {
+    pub fn new(box_key: &BoxKey) -> Delete {
+        Delete {
+            box_key: box_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Delete {
+    type Response = Deleted;
+
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+
+    fn path(&self) -> String {
+        format!("boxes/{}", self.box_key)
+    }
+}
+
+/// Delete a Box
+///
+/// API docs:
+/// <https://www.streak.com/api/#deletebox>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     streak::api::boxes::delete(&client, &acme.box_key).expect("delete the box");
+///     assert!(streak::api::boxes::list(&client, &pipeline.pipeline_key).expect("list boxes").is_empty());
+/// }
+/// ```
+pub fn delete(client: &Client, box_key: &BoxKey) -> Result<(), StreakError> {
+    let delete = Delete::new(box_key);
+    client.execute(&delete)?.check(delete.path())
+}
+
+/// Delete a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn delete_async(client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<()> {
+    let delete = Delete::new(box_key);
+    let resource = delete.path();
+    Box::new(client.execute(&delete).and_then(move |deleted| deleted.check(resource)))
+}
@@ -0,0 +1,345 @@
+use std::fmt;
+
+use serde_json::{self, Map, Value};
+
+use error::StreakError;
+use api::Timestamp;
+use api::pipelines::{Field, FieldItem, FieldType};
+use super::User;
+
+/// The value of a Field in a Box, as the service sent it.
+///
+/// Which variant a value is depends only on its JSON, so a date is an
+/// `Integer` and a dropdown choice is the `String` key of the choice. Use
+/// `typed` with the Field to get a `TypedValue` instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<Value>),
+    Object(Map<String, Value>),
+}
+
+/// The value of a Field in a Box, read according to the type of the Field.
+///
+/// ```rust
+/// extern crate serde_json;
+/// extern crate streak;
+///
+/// use streak::api::Timestamp;
+/// use streak::api::boxes::{FieldValue, TypedValue};
+/// use streak::api::pipelines::{fields, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     let client = streak.client();
+///     let close_date = streak.add_field(&pipeline.pipeline_key, "Close Date", FieldType::Date);
+///     let owner = streak.add_field(&pipeline.pipeline_key, "Owner", FieldType::Person);
+///     let labels = fields::create(&pipeline.pipeline_key, "Labels", FieldType::Tag)
+///         .items(&["hot", "enterprise"])
+///         .send(&client)
+///         .expect("create a field");
+///
+///     let date = FieldValue::Integer(1_500_000_000_000).typed(&close_date).expect("a date");
+///     assert_eq!(date, TypedValue::Date(Timestamp::from_millis(1_500_000_000_000)));
+///
+///     let keys = labels.items().iter().map(|item| item.key.clone().into()).collect();
+///     let tags = FieldValue::Array(keys).typed(&labels).expect("tags");
+///     assert_eq!(tags.item_names(), vec!["hot", "enterprise"]);
+///
+///     let ann = FieldValue::Array(vec![serde_json::from_str(r#"{"email": "ann@example.com"}"#).unwrap()]);
+///     let persons = ann.typed(&owner).expect("persons");
+///     assert_eq!(persons.as_persons().unwrap()[0].email, "ann@example.com");
+///     assert_eq!(FieldValue::from(persons), ann);
+///     assert!(FieldValue::Array(vec![serde_json::Map::new().into()]).typed(&owner).is_err());
+///
+///     assert_eq!(FieldValue::String("-12.5".into()).as_f64(), Some(-12.5));
+///     assert!(FieldValue::Bool(true).typed(&close_date).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    /// Text, which may well be a number; see `as_i64` and `as_f64`.
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Date(Timestamp),
+    Checkbox(bool),
+    /// The chosen item. Its name is empty when the choice has since been
+    /// removed from the Field.
+    Dropdown(FieldItem),
+    /// The tags, named like a `Dropdown` choice.
+    Tags(Vec<FieldItem>),
+    Persons(Vec<User>),
+    /// The result of the formula, which the service works out.
+    Formula(FieldValue),
+    /// The value of a Field of a type this library does not know about.
+    Unknown(FieldValue),
+}
+
+impl FieldValue {
+    /// Read the value according to the type of `field`. Fails when the value
+    /// does not suit that type.
+    pub fn typed(&self, field: &Field) -> Result<TypedValue, StreakError> {
+        let typed = match (&field.field_type, self) {
+            (&FieldType::Text, &FieldValue::String(ref s)) => TypedValue::Text(s.clone()),
+            (&FieldType::Text, &FieldValue::Integer(i)) => TypedValue::Integer(i),
+            (&FieldType::Text, &FieldValue::Float(f)) => TypedValue::Float(f),
+            (&FieldType::Date, value) => match value.as_i64() {
+                Some(millis) => TypedValue::Date(Timestamp::from_millis(millis)),
+                None => return Err(mismatch(field, value)),
+            },
+            (&FieldType::Checkbox, &FieldValue::Bool(b)) => TypedValue::Checkbox(b),
+            (&FieldType::Dropdown, &FieldValue::String(ref key)) => TypedValue::Dropdown(item(field, key)),
+            (&FieldType::Tag, &FieldValue::Array(ref keys)) => {
+                let tags = keys.iter()
+                    .map(|key| key.as_str().map(|key| item(field, key)).ok_or_else(|| mismatch(field, key)))
+                    .collect::<Result<_, _>>()?;
+                TypedValue::Tags(tags)
+            },
+            (&FieldType::Person, &FieldValue::Array(ref persons)) => TypedValue::Persons(users(field, persons)?),
+            (&FieldType::Formula, value) => TypedValue::Formula(value.clone()),
+            (&FieldType::Unknown(_), value) => TypedValue::Unknown(value.clone()),
+            (_, value) => return Err(mismatch(field, value)),
+        };
+        Ok(typed)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            FieldValue::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_string(&self) -> Option<String> {
+        self.as_str().map(|s| s.to_owned())
+    }
+
+    /// The value as a whole number, also when it is a float without a
+    /// fraction or a string holding a whole number.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            FieldValue::Integer(i) => Some(i),
+            FieldValue::Float(f) if f.fract() == 0.0 => Some(f as i64),
+            FieldValue::String(ref s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as a number, also when it is a string holding one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            FieldValue::Integer(i) => Some(i as f64),
+            FieldValue::Float(f) => Some(f),
+            FieldValue::String(ref s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            FieldValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// The value as a date, when it is a whole number of milliseconds as
+    /// `as_i64` reads it.
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        self.as_i64().map(Timestamp::from_millis)
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            FieldValue::Array(ref values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl TypedValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            TypedValue::Text(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The value as a whole number, also when it is text holding one.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            TypedValue::Integer(i) => Some(i),
+            TypedValue::Float(f) if f.fract() == 0.0 => Some(f as i64),
+            TypedValue::Text(ref s) => s.trim().parse().ok(),
+            TypedValue::Formula(ref value) => value.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// The value as a number, also when it is text holding one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            TypedValue::Integer(i) => Some(i as f64),
+            TypedValue::Float(f) => Some(f),
+            TypedValue::Text(ref s) => s.trim().parse().ok(),
+            TypedValue::Formula(ref value) => value.as_f64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            TypedValue::Checkbox(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match *self {
+            TypedValue::Date(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// The chosen dropdown item.
+    pub fn as_item(&self) -> Option<&FieldItem> {
+        match *self {
+            TypedValue::Dropdown(ref item) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// The name of the chosen dropdown item, or the names of the tags.
+    pub fn item_names(&self) -> Vec<&str> {
+        match *self {
+            TypedValue::Dropdown(ref item) => vec![item.name.as_str()],
+            TypedValue::Tags(ref items) => items.iter().map(|i| i.name.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn as_persons(&self) -> Option<&[User]> {
+        match *self {
+            TypedValue::Persons(ref persons) => Some(persons),
+            _ => None,
+        }
+    }
+}
+
+/// The item of `field` with the key `key`.
+fn item(field: &Field, key: &str) -> FieldItem {
+    field.items().iter()
+        .find(|i| i.key == key)
+        .cloned()
+        .unwrap_or_else(|| FieldItem {
+            key: key.into(),
+            name: String::new(),
+        })
+}
+
+/// The users a Person field holds, failing unless every entry is one.
+pub(crate) fn users(field: &Field, persons: &[Value]) -> Result<Vec<User>, StreakError> {
+    persons.iter()
+        .map(|person| user(person).ok_or_else(|| mismatch(field, person)))
+        .collect()
+}
+
+/// `value` as a user, when it is an object naming one by key or by email.
+/// Every property of a `User` has a default, so any other object would do.
+pub(crate) fn user(value: &Value) -> Option<User> {
+    match serde_json::from_value::<User>(value.clone()) {
+        Ok(user) => {
+            if user.user_key.as_str().is_empty() && user.email.is_empty() {
+                None
+            } else {
+                Some(user)
+            }
+        },
+        Err(_) => None,
+    }
+}
+
+/// A user as a Person field holds it, without the properties that are not
+/// known, so that no empty key is sent.
+fn person(user: &User) -> Value {
+    let mut person = Map::new();
+    let properties = [
+        ("userKey", user.user_key.as_str()),
+        ("email", user.email.as_str()),
+        ("displayName", user.display_name.as_str()),
+        ("fullName", user.full_name.as_str()),
+        ("image", user.image.as_str()),
+    ];
+    for &(name, value) in &properties {
+        if !value.is_empty() {
+            person.insert(name.into(), value.into());
+        }
+    }
+    for (name, value) in &user.extra {
+        person.insert(name.clone(), value.clone());
+    }
+    Value::Object(person)
+}
+
+pub(crate) fn mismatch<T: fmt::Debug>(field: &Field, value: &T) -> StreakError {
+    StreakError::InvalidFieldValue(format!("{} is a {} field and can not hold {:?}", field.name, field.field_type, value))
+}
+
+impl From<TypedValue> for FieldValue {
+    fn from(value: TypedValue) -> FieldValue {
+        let keys = |items: Vec<FieldItem>| items.into_iter().map(|i| Value::String(i.key)).collect();
+        match value {
+            TypedValue::Text(s) => FieldValue::String(s),
+            TypedValue::Integer(i) => FieldValue::Integer(i),
+            TypedValue::Float(f) => FieldValue::Float(f),
+            TypedValue::Date(t) => FieldValue::Integer(t.millis()),
+            TypedValue::Checkbox(b) => FieldValue::Bool(b),
+            TypedValue::Dropdown(item) => FieldValue::String(item.key),
+            TypedValue::Tags(items) => FieldValue::Array(keys(items)),
+            TypedValue::Persons(persons) => FieldValue::Array(persons.iter().map(person).collect()),
+            TypedValue::Formula(value) | TypedValue::Unknown(value) => value,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FieldValue {
+    fn from(s: &'a str) -> FieldValue {
+        FieldValue::String(s.into())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(s: String) -> FieldValue {
+        FieldValue::String(s)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(i: i64) -> FieldValue {
+        FieldValue::Integer(i)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(f: f64) -> FieldValue {
+        FieldValue::Float(f)
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(b: bool) -> FieldValue {
+        FieldValue::Bool(b)
+    }
+}
+
+impl From<Timestamp> for FieldValue {
+    fn from(t: Timestamp) -> FieldValue {
+        FieldValue::Integer(t.millis())
+    }
+}
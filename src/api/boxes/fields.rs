@@ -0,0 +1,290 @@
+//! Box field values
+//!
+//! A Box holds a value for the Fields of its Pipeline. These functions take
+//! the Pipeline so a Field can be given by name as well as by key, and so a
+//! value can be checked against the type of the Field before it is sent.
+
+use reqwest::Method;
+use serde_json::Value;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::{future, Future};
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::BoxKey;
+use api::pipelines::{Field, FieldType, Pipeline};
+use super::FieldValue;
+use super::field_value::{mismatch, user, users};
+
+/// The value of a Field in a Box.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxField {
+    /// The key of the Field.
+    pub key: String,
+    /// `None` when the Box has no value for the Field.
+    #[serde(default)]
+    pub value: Option<FieldValue>,
+}
+
+/// The endpoint getting the value of a Field in a Box.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub box_key: BoxKey,
+    pub field_key: String,
+}
+
+impl Get {
+    pub fn new(box_key: &BoxKey, field_key: &str) -> Get {
+        Get {
+            box_key: box_key.clone(),
+            field_key: field_key.into(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = BoxField;
+
+    fn path(&self) -> String {
+        format!("boxes/{}/fields/{}", self.box_key, self.field_key)
+    }
+}
+
+/// The endpoint setting, or with a `None` value clearing, the value of a
+/// Field in a Box. It sends the value as it is; `boxes::fields::set` checks
+/// it first.
+#[derive(Debug, Clone, Serialize)]
+pub struct Set {
+    #[serde(skip)]
+    pub box_key: BoxKey,
+    #[serde(skip)]
+    pub field_key: String,
+    pub value: Option<FieldValue>,
+}
+
+impl Set {
+    pub fn new(box_key: &BoxKey, field_key: &str, value: Option<FieldValue>) -> Set {
+        Set {
+            box_key: box_key.clone(),
+            field_key: field_key.into(),
+            value: value,
+        }
+    }
+}
+
+impl Endpoint for Set {
+    type Response = BoxField;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn path(&self) -> String {
+        format!("boxes/{}/fields/{}", self.box_key, self.field_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::json(self)?))
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// The Field of `pipeline` with the key or name `field`.
+fn find<'a>(pipeline: &'a Pipeline, field: &str) -> Result<&'a Field, StreakError> {
+    pipeline.field(field).ok_or_else(|| {
+        StreakError::InvalidFieldValue(format!("The pipeline {} has no field {}", pipeline.name, field))
+    })
+}
+
+/// Check that `field` can hold `value`, by the rules `FieldValue::typed`
+/// reads values with. Dates are sent as an `Integer`, and dropdown choices
+/// and tags may be given by name and are replaced by their key.
+fn check(field: &Field, value: FieldValue) -> Result<FieldValue, StreakError> {
+    match (&field.field_type, value) {
+        (&FieldType::Text, value @ FieldValue::String(_)) |
+        (&FieldType::Text, value @ FieldValue::Integer(_)) |
+        (&FieldType::Text, value @ FieldValue::Float(_)) |
+        (&FieldType::Checkbox, value @ FieldValue::Bool(_)) => Ok(value),
+        (&FieldType::Date, value) => match value.as_i64() {
+            Some(millis) => Ok(FieldValue::Integer(millis)),
+            None => Err(mismatch(field, &value)),
+        },
+        (&FieldType::Person, FieldValue::Array(persons)) => {
+            users(field, &persons)?;
+            Ok(FieldValue::Array(persons))
+        },
+        (&FieldType::Unknown(_), value) => {
+            if any_field_holds(&value) {
+                Ok(value)
+            } else {
+                Err(mismatch(field, &value))
+            }
+        },
+        (&FieldType::Dropdown, FieldValue::String(item)) => item_key(field, &item).map(FieldValue::String),
+        (&FieldType::Tag, FieldValue::Array(tags)) => {
+            let tags = tags.into_iter()
+                .map(|tag| match tag {
+                    Value::String(tag) => item_key(field, &tag).map(Value::String),
+                    other => Err(mismatch(field, &other)),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(FieldValue::Array(tags))
+        },
+        (&FieldType::Formula, _) => {
+            Err(StreakError::InvalidFieldValue(format!("{} is a formula field and can not be set", field.name)))
+        },
+        (_, value) => Err(mismatch(field, &value)),
+    }
+}
+
+/// Whether a Field of one of the known types could hold `value`, which is
+/// all that can be checked for a Field of a type this library does not know.
+fn any_field_holds(value: &FieldValue) -> bool {
+    match *value {
+        FieldValue::Integer(_) | FieldValue::Float(_) | FieldValue::Bool(_) | FieldValue::String(_) => true,
+        FieldValue::Array(ref values) => {
+            values.iter().all(Value::is_string) ||
+                values.iter().all(|v| user(v).is_some())
+        },
+        FieldValue::Object(_) => false,
+    }
+}
+
+fn item_key(field: &Field, item: &str) -> Result<String, StreakError> {
+    field.items().iter()
+        .find(|i| i.key == item)
+        .or_else(|| field.item(item))
+        .map(|i| i.key.clone())
+        .ok_or_else(|| StreakError::InvalidFieldValue(format!("{} is not one of the items of {}", item, field.name)))
+}
+
+/// Get the value of a Field in a Box
+///
+/// `field` is the key or the name of a Field of `pipeline`, the Pipeline of
+/// the Box.
+///
+/// API docs:
+/// <https://www.streak.com/api/#getfieldvalue>
+pub fn get(client: &Client, pipeline: &Pipeline, box_key: &BoxKey, field: &str) -> Result<Option<FieldValue>, StreakError> {
+    let field = find(pipeline, field)?;
+    client.execute(&Get::new(box_key, &field.key)).map(|f| f.value)
+}
+
+/// Get the value of a Field in a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, pipeline: &Pipeline, box_key: &BoxKey, field: &str) -> StreakFuture<Option<FieldValue>> {
+    match find(pipeline, field) {
+        Ok(field) => Box::new(client.execute(&Get::new(box_key, &field.key)).map(|f| f.value)),
+        Err(e) => Box::new(future::err(e)),
+    }
+}
+
+/// Set the value of a Field in a Box
+///
+/// `field` is the key or the name of a Field of `pipeline`, the Pipeline of
+/// the Box. The value must suit the type of the Field: a `String` or a
+/// number for text, a whole number of milliseconds for a date, a `Bool` for
+/// a checkbox, the key or name of one of its choices for a dropdown, an
+/// `Array` of tag keys or names for tags, and an `Array` of users, each
+/// with a `userKey` or an `email`, for a person field. Anything else is an `InvalidFieldValue` error and nothing
+/// is sent.
+///
+/// API docs:
+/// <https://www.streak.com/api/#editfieldvalue>
+///
+/// ```rust
+/// extern crate serde_json;
+/// extern crate streak;
+///
+/// use streak::StreakError;
+/// use streak::api::boxes::{self, FieldValue};
+/// use streak::api::pipelines::{self, fields, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     streak.add_field(&pipeline.pipeline_key, "Deal Size", FieldType::Text);
+///     streak.add_field(&pipeline.pipeline_key, "Close Date", FieldType::Date);
+///     streak.add_field(&pipeline.pipeline_key, "Owner", FieldType::Person);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///     fields::create(&pipeline.pipeline_key, "Region", FieldType::Dropdown)
+///         .items(&["EMEA", "APAC"])
+///         .send(&client)
+///         .expect("create a field");
+///     let pipeline = pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///
+///     boxes::fields::set(&client, &pipeline, &acme.box_key, "Deal Size", FieldValue::Integer(12000))
+///         .expect("set the deal size");
+///     boxes::fields::set(&client, &pipeline, &acme.box_key, "Region", FieldValue::String("APAC".into()))
+///         .expect("set the region");
+///
+///     let region = boxes::fields::get(&client, &pipeline, &acme.box_key, "Region").expect("get the region");
+///     let apac = &pipeline.field("Region").unwrap().item("APAC").unwrap().key;
+///     assert_eq!(region.and_then(|v| v.as_string()), Some(apac.clone()));
+///
+///     match boxes::fields::set(&client, &pipeline, &acme.box_key, "Region", FieldValue::Bool(true)) {
+///         Err(StreakError::InvalidFieldValue(_)) => {},
+///         other => panic!("expected the value to be refused, got {:?}", other),
+///     }
+///     let not_users = vec![
+///         FieldValue::Array(vec![1.into(), true.into()]),
+///         FieldValue::Array(vec![serde_json::Map::new().into()]),
+///     ];
+///     for value in not_users {
+///         match boxes::fields::set(&client, &pipeline, &acme.box_key, "Owner", value) {
+///             Err(StreakError::InvalidFieldValue(_)) => {},
+///             other => panic!("expected the value to be refused, got {:?}", other),
+///         }
+///     }
+///
+///     let close_date = boxes::fields::set(&client, &pipeline, &acme.box_key, "Close Date", "1500000000000".into())
+///         .expect("set the close date");
+///     assert_eq!(close_date.value, Some(FieldValue::Integer(1_500_000_000_000)));
+///
+///     boxes::fields::clear(&client, &pipeline, &acme.box_key, "Deal Size").expect("clear the deal size");
+///     let acme = boxes::get(&client, &acme.box_key).expect("get the box");
+///     assert!(acme.field_value(&pipeline, "Deal Size").is_none());
+///     assert!(acme.field_value(&pipeline, "Region").is_some());
+/// }
+/// ```
+pub fn set(client: &Client, pipeline: &Pipeline, box_key: &BoxKey, field: &str, value: FieldValue) -> Result<BoxField, StreakError> {
+    let field = find(pipeline, field)?;
+    let value = check(field, value)?;
+    client.execute(&Set::new(box_key, &field.key, Some(value)))
+}
+
+/// Set the value of a Field in a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn set_async(client: &AsyncClient, pipeline: &Pipeline, box_key: &BoxKey, field: &str, value: FieldValue) -> StreakFuture<BoxField> {
+    match find(pipeline, field).and_then(|field| check(field, value).map(|value| (field, value))) {
+        Ok((field, value)) => client.execute(&Set::new(box_key, &field.key, Some(value))),
+        Err(e) => Box::new(future::err(e)),
+    }
+}
+
+/// Remove the value of a Field from a Box
+///
+/// API docs:
+/// <https://www.streak.com/api/#editfieldvalue>
+pub fn clear(client: &Client, pipeline: &Pipeline, box_key: &BoxKey, field: &str) -> Result<(), StreakError> {
+    let field = find(pipeline, field)?;
+    client.execute(&Set::new(box_key, &field.key, None)).map(|_| ())
+}
+
+/// Remove the value of a Field from a Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn clear_async(client: &AsyncClient, pipeline: &Pipeline, box_key: &BoxKey, field: &str) -> StreakFuture<()> {
+    match find(pipeline, field) {
+        Ok(field) => Box::new(client.execute(&Set::new(box_key, &field.key, None)).map(|_| ())),
+        Err(e) => Box::new(future::err(e)),
+    }
+}
@@ -1,11 +1,35 @@
 //! Box actions
 
-use serde_json;
-
 use error::StreakError;
 use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::BoxKey;
 use super::Box;
 
+/// The endpoint getting a specific Box.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub box_key: BoxKey,
+}
+
+impl Get {
+    pub fn new(box_key: &BoxKey) -> Get {
+        Get {
+            box_key: box_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = Box;
+
+    fn path(&self) -> String {
+        format!("boxes/{}", self.box_key)
+    }
+}
+
 /// Get a specific Box
 ///
 /// API docs:
@@ -17,7 +41,7 @@ use super::Box;
 /// use streak::Client;
 ///
 /// fn main() {
-///     let client = streak::Client::example();
+///     let client = streak::Client::example("boxes_get");
 ///     let pipelines = streak::api::pipelines::list(&client).expect("list pipelines");
 ///     let pipeline_key = &pipelines[0].pipeline_key;
 ///     let boxes = streak::api::boxes::list(&client, pipeline_key).expect("list boxes by pipeline");
@@ -27,8 +51,12 @@ use super::Box;
 ///     assert!(get_box.name != "");
 /// }
 /// ```
-pub fn get(client: &Client, box_key: &str) -> Result<Box, StreakError> {
-    let res = client.get(&format!("boxes/{}", box_key), ())?;
-    let result = serde_json::from_value(res.clone())?;
-    Ok(result)
+pub fn get(client: &Client, box_key: &BoxKey) -> Result<Box, StreakError> {
+    client.execute(&Get::new(box_key))
+}
+
+/// Get a specific Box without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<Box> {
+    client.execute(&Get::new(box_key))
 }
@@ -1,9 +1,70 @@
-use serde_json;
+use serde_url_params;
 
 use error::StreakError;
 use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture, StreakStream};
+#[cfg(feature = "async")]
+use pagination;
+use pagination::{Page, Paginated};
+use streaming::JsonArray;
+use api::PipelineKey;
 use super::Box;
 
+/// How many Boxes `boxes::iter` asks for at a time.
+pub const PAGE_SIZE: u32 = 100;
+
+/// The endpoint listing the Boxes in a Pipeline, all at once or a page at a
+/// time.
+#[derive(Debug, Clone, Serialize)]
+pub struct List {
+    #[serde(skip)]
+    pub pipeline_key: PipelineKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl List {
+    pub fn new(pipeline_key: &PipelineKey) -> List {
+        List {
+            pipeline_key: pipeline_key.clone(),
+            page: None,
+            limit: None,
+        }
+    }
+
+    /// Only list the `limit` Boxes on page `page`, counting from 0.
+    pub fn page(mut self, page: u32, limit: u32) -> List {
+        self.page = Some(page);
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Endpoint for List {
+    type Response = Vec<Box>;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/boxes", self.pipeline_key)
+    }
+
+    fn query(&self) -> Result<String, StreakError> {
+        Ok(serde_url_params::to_string(self)?)
+    }
+}
+
+fn page(boxes: Vec<Box>) -> Page<Box> {
+    Page {
+        more: boxes.len() == PAGE_SIZE as usize,
+        items: boxes,
+    }
+}
+
 /// List all boxes in a Pipeline
 ///
 /// API docs:
@@ -15,7 +76,7 @@ use super::Box;
 /// use streak::Client;
 ///
 /// fn main() {
-///     let client = streak::Client::example();
+///     let client = streak::Client::example("boxes_list");
 ///     let pipelines = streak::api::pipelines::list(&client).expect("list all pipelines");
 ///     let pipeline_key = &pipelines[0].pipeline_key;
 ///     let boxes = streak::api::boxes::list(&client, pipeline_key).expect("list boxes by pipeline");
@@ -24,8 +85,82 @@ use super::Box;
 ///     assert!(boxes[0].name != "");
 /// }
 /// ```
-pub fn list(client: &Client, pipeline_key: &str) -> Result<Vec<Box>, StreakError> {
-    let res = client.get(&format!("pipelines/{}/boxes", pipeline_key), ())?;
-    let result = serde_json::from_value(res.clone())?;
-    Ok(result)
+pub fn list(client: &Client, pipeline_key: &PipelineKey) -> Result<Vec<Box>, StreakError> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// List all boxes in a Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn list_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakFuture<Vec<Box>> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// Read all boxes in a Pipeline one at a time from a single response, as
+/// they arrive
+///
+/// Unlike `list`, only the box being read is held in memory, so this suits
+/// pipelines too big to load at once.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     for i in 0..1000 {
+///         streak.add_box(&pipeline.pipeline_key, &format!("Lead {}", i), &pipeline.stage_order[0]);
+///     }
+///     let client = streak.client();
+///
+///     let boxes = streak::api::boxes::list_streaming(&client, &pipeline.pipeline_key).expect("list boxes");
+///     let mut count = 0;
+///     for b in boxes {
+///         assert_eq!(b.expect("a box").pipeline_key, pipeline.pipeline_key);
+///         count += 1;
+///     }
+///     assert_eq!(count, 1000);
+/// }
+/// ```
+pub fn list_streaming(client: &Client, pipeline_key: &PipelineKey) -> Result<JsonArray<Box>, StreakError> {
+    client.execute_iter(&List::new(pipeline_key))
+}
+
+/// Iterate over all boxes in a Pipeline, fetching `PAGE_SIZE` of them at a
+/// time
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     for i in 0..250 {
+///         streak.add_box(&pipeline.pipeline_key, &format!("Lead {}", i), &pipeline.stage_order[0]);
+///     }
+///     let client = streak.client();
+///
+///     let mut count = 0;
+///     for b in streak::api::boxes::iter(&client, &pipeline.pipeline_key) {
+///         let b = b.expect("a box");
+///         assert_eq!(b.name, format!("Lead {}", count));
+///         count += 1;
+///     }
+///     assert_eq!(count, 250);
+/// }
+/// ```
+pub fn iter<'a>(client: &'a Client, pipeline_key: &PipelineKey) -> Paginated<'a, Box> {
+    let list = List::new(pipeline_key);
+    Paginated::new(move |n| client.execute(&list.clone().page(n, PAGE_SIZE)).map(page))
+}
+
+/// Stream all boxes in a Pipeline, fetching `PAGE_SIZE` of them at a time.
+#[cfg(feature = "async")]
+pub fn iter_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakStream<Box> {
+    let client = client.clone();
+    let list = List::new(pipeline_key);
+    pagination::stream(move |n| client.execute(&list.clone().page(n, PAGE_SIZE)).map(page))
 }
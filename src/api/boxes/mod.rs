@@ -1,39 +1,88 @@
 pub mod list;
-pub use self::list::list;
+pub use self::list::{iter, list, list_streaming, List};
+#[cfg(feature = "async")]
+pub use self::list::{iter_async, list_async};
 
 pub mod get;
-pub use self::get::get;
+pub use self::get::{get, Get};
+#[cfg(feature = "async")]
+pub use self::get::get_async;
+
+pub mod create;
+pub use self::create::{create, Create};
+#[cfg(feature = "async")]
+pub use self::create::create_async;
+
+pub mod update;
+pub use self::update::{update, Update};
+
+pub mod delete;
+pub use self::delete::{delete, Delete};
+#[cfg(feature = "async")]
+pub use self::delete::delete_async;
+
+pub mod fields;
+
+pub mod comments;
+pub use self::comments::Comment;
+
+pub mod transitions;
+pub use self::transitions::{advance, back, move_to_stage, StageRules};
+#[cfg(feature = "async")]
+pub use self::transitions::{advance_async, back_async, move_to_stage_async};
+
+mod field_value;
+pub use self::field_value::{FieldValue, TypedValue};
 
 use std::collections::HashMap;
 
 use serde_json::Value;
 
+use error::StreakError;
+use api::{BoxKey, ContactKey, Model, PipelineKey, StageKey, Timestamp, UserKey};
+use api::model;
+use api::pipelines::Pipeline;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Box {
-    pub last_saved_timestamp: u64,
-    pub pipeline_key: String,
-    pub creator_key: String,
-    pub creation_timestamp: u64,
-    pub last_updated_timestamp: u64,
-    pub last_stage_change_timestamp: u64,
-    pub last_comment_timestamp: Option<u64>,
+    pub last_saved_timestamp: Timestamp,
+    pub pipeline_key: PipelineKey,
+    pub creator_key: UserKey,
+    pub creation_timestamp: Timestamp,
+    pub last_updated_timestamp: Timestamp,
+    pub last_stage_change_timestamp: Timestamp,
+    pub last_comment_timestamp: Option<Timestamp>,
+    #[serde(default)]
     pub total_number_of_emails: u32,
+    #[serde(default)]
     pub total_number_of_sent_emails: u32,
+    #[serde(default)]
     pub total_number_of_received_emails: u32,
     pub name: String,
     pub notes: Option<String>,
+    #[serde(default)]
     pub assigned_to_sharing_entries: Vec<User>,
+    #[serde(default)]
     pub creator_sharing_entry: User,
+    #[serde(default)]
     pub follower_sharing_entries: Vec<User>,
-    pub stage_key: String,
-    pub follower_keys: Vec<String>,
-    pub linked_box_keys: Vec<String>,
+    pub stage_key: StageKey,
+    #[serde(default)]
+    pub follower_keys: Vec<UserKey>,
+    #[serde(default)]
+    pub linked_box_keys: Vec<BoxKey>,
+    #[serde(default)]
     pub email_addresses_auto_extracted: Vec<String>,
+    #[serde(default)]
     pub email_addresses_blacklist: Vec<String>,
+    #[serde(default)]
     pub email_addresses: Vec<String>,
+    #[serde(default)]
     pub task_complete_count: u32,
+    #[serde(default)]
     pub task_incomplete_count: u32,
+    #[serde(default)]
     pub task_overdue_count: u32,
     //pub task_assignee_key_set: Vec<>,
     //pub overdue_task_assignee_key_set: Vec<>,
@@ -41,54 +90,100 @@ pub struct Box {
     //pub task_assignee_sharing_entry_set: Vec<>,
     //pub overdue_task_assignee_sharing_entry_set: Vec<>,
     //pub incomplete_task_assignee_sharing_entry_set: Vec<>,
+    #[serde(default)]
     pub task_total: u32,
+    #[serde(default)]
     pub call_log_count: u32,
+    #[serde(default)]
     pub meeting_notes_count: u32,
+    #[serde(default)]
     pub total_call_log_duration: u32,
+    #[serde(default)]
     pub total_meeting_notes_duration: u32,
+    #[serde(default)]
     pub follower_count: u32,
+    #[serde(default)]
     pub comment_count: u32,
+    #[serde(default)]
     pub gmail_thread_count: u32,
+    #[serde(default)]
     pub file_count: u32,
+    #[serde(default)]
     pub fields: HashMap<String, FieldValue>,
-    pub box_key: String,
-    pub key: String,
+    pub box_key: BoxKey,
+    pub key: BoxKey,
+    #[serde(default)]
     pub freshness: f64,
     pub contacts: Option<Vec<ContactHandle>>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum FieldValue {
-    Integer(u64),
-    Bool(bool),
-    String(String),
-    Array(Vec<Value>),
+impl Model for Box {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[
+            ("assignedToSharingEntries", &self.assigned_to_sharing_entries),
+            ("creatorSharingEntry", &self.creator_sharing_entry),
+            ("followerSharingEntries", &self.follower_sharing_entries),
+            ("contacts", &self.contacts),
+        ])
+    }
 }
 
-impl FieldValue {
-    pub fn as_string(&self) -> Option<String> {
-        if let &FieldValue::String(ref s) = self {
-            Some(s.clone())
-        } else {
-            None
+impl Box {
+    /// The value of the field with the key or name `field` of `pipeline`, the
+    /// Pipeline of the Box.
+    pub fn field_value(&self, pipeline: &Pipeline, field: &str) -> Option<&FieldValue> {
+        pipeline.field(field).and_then(|f| self.fields.get(&f.key))
+    }
+
+    /// The value of a field like `field_value`, read according to the type
+    /// of the field. Fails when the value does not suit that type.
+    pub fn typed_field_value(&self, pipeline: &Pipeline, field: &str) -> Result<Option<TypedValue>, StreakError> {
+        let field = match pipeline.field(field) {
+            Some(field) => field,
+            None => return Ok(None),
+        };
+        match self.fields.get(&field.key) {
+            Some(value) => value.typed(field).map(Some),
+            None => Ok(None),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct User {
-    display_name: String,
-    full_name: String,
-    email: String,
-    image: String,
-    user_key: String,
+    pub display_name: String,
+    pub full_name: String,
+    pub email: String,
+    pub image: String,
+    pub user_key: UserKey,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for User {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContactHandle {
+    #[serde(default)]
     pub is_starred: bool,
-    pub key: String,
+    pub key: ContactKey,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for ContactHandle {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
@@ -0,0 +1,277 @@
+//! Moving Boxes between the Stages of their Pipeline
+//!
+//! A move names a Stage by key or by name, or goes to the next or previous
+//! Stage in the `stage_order` of the Pipeline. It is checked against the
+//! Pipeline, and against any `StageRules`, before the Box is updated.
+
+use error::StreakError;
+use client::Client;
+#[cfg(feature = "async")]
+use futures::{future, Future};
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{BoxKey, StageKey};
+use api::pipelines::{self, Pipeline};
+use super::{get, update, FieldValue};
+#[cfg(feature = "async")]
+use super::get_async;
+
+/// Where a Box is asked to move.
+#[derive(Debug, Clone)]
+enum Target {
+    Stage(String),
+    Next,
+    Previous,
+}
+
+/// Rules for moving Boxes between Stages, enforced before the Box is
+/// updated. A move that breaks one is an `InvalidStageMove` error.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::StreakError;
+/// use streak::api::boxes::{self, FieldValue, StageRules};
+/// use streak::api::pipelines::{self, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Demo", "Won"]);
+///     streak.add_field(&pipeline.pipeline_key, "Deal Size", FieldType::Text);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     let rules = StageRules::new()
+///         .forbid_skipping()
+///         .require_fields("Won", &["Deal Size"]);
+///
+///     match rules.move_to_stage(&client, &acme.box_key, "Won") {
+///         Err(StreakError::InvalidStageMove(_)) => {},
+///         other => panic!("expected the skip to be refused, got {:?}", other),
+///     }
+///     rules.advance(&client, &acme.box_key).expect("move to the demo");
+///     match rules.advance(&client, &acme.box_key) {
+///         Err(StreakError::InvalidStageMove(_)) => {},
+///         other => panic!("expected the deal size to be required, got {:?}", other),
+///     }
+///
+///     let pipeline = pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///     boxes::fields::set(&client, &pipeline, &acme.box_key, "Deal Size", FieldValue::Integer(12000))
+///         .expect("set the deal size");
+///     let acme = rules.advance(&client, &acme.box_key).expect("win the deal");
+///     assert_eq!(acme.stage_key, pipeline.stage_order[2]);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StageRules {
+    forbid_skipping: bool,
+    required_fields: Vec<(String, Vec<String>)>,
+}
+
+impl StageRules {
+    /// No rules: every move to a Stage of the Pipeline is allowed.
+    pub fn new() -> StageRules {
+        StageRules::default()
+    }
+
+    /// Only let Boxes move forward one Stage at a time. Moving back is
+    /// always allowed.
+    pub fn forbid_skipping(mut self) -> StageRules {
+        self.forbid_skipping = true;
+        self
+    }
+
+    /// Require `fields`, by key or name, to have a value before a Box enters
+    /// `stage`, given by key or name. Rules for Stages a Pipeline does not
+    /// have are ignored.
+    pub fn require_fields(mut self, stage: &str, fields: &[&str]) -> StageRules {
+        self.required_fields.push((stage.into(), fields.iter().map(|&f| f.into()).collect()));
+        self
+    }
+
+    /// Check a move of `b` to the Stage `to` of `pipeline`, the Pipeline of
+    /// the Box, without sending anything.
+    pub fn check(&self, pipeline: &Pipeline, b: &super::Box, to: &StageKey) -> Result<(), StreakError> {
+        let from = position(pipeline, &b.stage_key)?;
+        if self.forbid_skipping && position(pipeline, to)? > from + 1 {
+            return Err(invalid(format!("{} can only move forward one stage at a time", b.name)));
+        }
+
+        for &(ref stage, ref fields) in &self.required_fields {
+            let stage = match pipeline.stage(stage) {
+                Some(stage) if stage.key == *to => stage,
+                _ => continue,
+            };
+            for field in fields {
+                if pipeline.field(field).is_none() {
+                    return Err(invalid(format!("There is no field called {} in the pipeline {}", field, pipeline.name)));
+                }
+                if !b.field_value(pipeline, field).map_or(false, is_filled) {
+                    return Err(invalid(format!("{} needs a value for {} before it can enter {}", b.name, field, stage.name)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Move a Box to `stage`, a Stage key or name, following the rules.
+    pub fn move_to_stage(&self, client: &Client, box_key: &BoxKey, stage: &str) -> Result<super::Box, StreakError> {
+        self.send(client, box_key, Target::Stage(stage.into()))
+    }
+
+    /// Move a Box to the next Stage of its Pipeline, following the rules.
+    pub fn advance(&self, client: &Client, box_key: &BoxKey) -> Result<super::Box, StreakError> {
+        self.send(client, box_key, Target::Next)
+    }
+
+    /// Move a Box to the previous Stage of its Pipeline, following the rules.
+    pub fn back(&self, client: &Client, box_key: &BoxKey) -> Result<super::Box, StreakError> {
+        self.send(client, box_key, Target::Previous)
+    }
+
+    /// Move a Box without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn move_to_stage_async(&self, client: &AsyncClient, box_key: &BoxKey, stage: &str) -> StreakFuture<super::Box> {
+        self.send_async(client, box_key, Target::Stage(stage.into()))
+    }
+
+    /// Advance a Box without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn advance_async(&self, client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<super::Box> {
+        self.send_async(client, box_key, Target::Next)
+    }
+
+    /// Move a Box back without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn back_async(&self, client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<super::Box> {
+        self.send_async(client, box_key, Target::Previous)
+    }
+
+    fn send(&self, client: &Client, box_key: &BoxKey, target: Target) -> Result<super::Box, StreakError> {
+        let b = get(client, box_key)?;
+        let pipeline = pipelines::get(client, &b.pipeline_key)?;
+        match self.plan(&pipeline, &b, &target)? {
+            Some(to) => update(box_key).stage_key(&to).send(client),
+            None => Ok(b),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn send_async(&self, client: &AsyncClient, box_key: &BoxKey, target: Target) -> StreakFuture<super::Box> {
+        let rules = self.clone();
+        let pipelines_client = client.clone();
+        let update_client = client.clone();
+        Box::new(get_async(client, box_key)
+            .and_then(move |b| pipelines::get_async(&pipelines_client, &b.pipeline_key).map(|pipeline| (b, pipeline)))
+            .and_then(move |(b, pipeline)| match rules.plan(&pipeline, &b, &target) {
+                Ok(Some(to)) => future::Either::A(update(&b.box_key).stage_key(&to).send_async(&update_client)),
+                Ok(None) => future::Either::B(future::ok(b)),
+                Err(e) => future::Either::B(future::err(e)),
+            }))
+    }
+
+    /// The Stage a move of `b` ends in, or `None` when the Box is in it
+    /// already.
+    fn plan(&self, pipeline: &Pipeline, b: &super::Box, target: &Target) -> Result<Option<StageKey>, StreakError> {
+        let from = position(pipeline, &b.stage_key)?;
+        let to = match *target {
+            Target::Stage(ref stage) => match pipeline.stage(stage) {
+                Some(stage) => stage.key.clone(),
+                None => return Err(invalid(format!("There is no stage called {} in the pipeline {}", stage, pipeline.name))),
+            },
+            Target::Next => match pipeline.stage_order.get(from + 1) {
+                Some(key) => key.clone(),
+                None => return Err(invalid(format!("{} is in the last stage already", b.name))),
+            },
+            Target::Previous => match from.checked_sub(1) {
+                Some(previous) => pipeline.stage_order[previous].clone(),
+                None => return Err(invalid(format!("{} is in the first stage already", b.name))),
+            },
+        };
+        if to == b.stage_key {
+            return Ok(None);
+        }
+        self.check(pipeline, b, &to)?;
+        Ok(Some(to))
+    }
+}
+
+/// Move a Box to another Stage of its Pipeline
+///
+/// `stage` is the key or the name of the Stage. Moving a Box to the Stage
+/// it is in leaves it as it is. Use `StageRules` to restrict moves further.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::boxes;
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Demo", "Won"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     let acme = boxes::move_to_stage(&client, &acme.box_key, "Won").expect("win the deal");
+///     assert_eq!(acme.stage_key, pipeline.stage_order[2]);
+///
+///     let acme = boxes::back(&client, &acme.box_key).expect("move back to the demo");
+///     assert_eq!(acme.stage_key, pipeline.stage_order[1]);
+///
+///     let acme = boxes::advance(&client, &acme.box_key).expect("advance to won");
+///     assert_eq!(acme.stage_key, pipeline.stage_order[2]);
+///     assert!(boxes::advance(&client, &acme.box_key).is_err());
+/// }
+/// ```
+pub fn move_to_stage(client: &Client, box_key: &BoxKey, stage: &str) -> Result<super::Box, StreakError> {
+    StageRules::default().move_to_stage(client, box_key, stage)
+}
+
+/// Move a Box to the next Stage of its Pipeline.
+pub fn advance(client: &Client, box_key: &BoxKey) -> Result<super::Box, StreakError> {
+    StageRules::default().advance(client, box_key)
+}
+
+/// Move a Box to the previous Stage of its Pipeline.
+pub fn back(client: &Client, box_key: &BoxKey) -> Result<super::Box, StreakError> {
+    StageRules::default().back(client, box_key)
+}
+
+/// Move a Box to another Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn move_to_stage_async(client: &AsyncClient, box_key: &BoxKey, stage: &str) -> StreakFuture<super::Box> {
+    StageRules::default().move_to_stage_async(client, box_key, stage)
+}
+
+/// Move a Box to the next Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn advance_async(client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<super::Box> {
+    StageRules::default().advance_async(client, box_key)
+}
+
+/// Move a Box to the previous Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn back_async(client: &AsyncClient, box_key: &BoxKey) -> StreakFuture<super::Box> {
+    StageRules::default().back_async(client, box_key)
+}
+
+/// Where the Stage `key` comes in the `stage_order` of `pipeline`.
+fn position(pipeline: &Pipeline, key: &StageKey) -> Result<usize, StreakError> {
+    pipeline.stage_order.iter()
+        .position(|k| k == key)
+        .ok_or_else(|| invalid(format!("The stage {} is not one of the pipeline {}", key, pipeline.name)))
+}
+
+fn is_filled(value: &FieldValue) -> bool {
+    match *value {
+        FieldValue::String(ref s) => !s.is_empty(),
+        FieldValue::Array(ref items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+fn invalid(message: String) -> StreakError {
+    StreakError::InvalidStageMove(message)
+}
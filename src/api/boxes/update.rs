@@ -0,0 +1,143 @@
+//! Box actions
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{BoxKey, StageKey, UserKey};
+use super::Box;
+
+/// The endpoint editing a Box. Build it with `boxes::update`.
+///
+/// Only the properties that were set are sent, everything else is left as it
+/// is.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    #[serde(skip)]
+    box_key: BoxKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stage_key: Option<StageKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assigned_to_sharing_entries: Option<Vec<Assignee>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follower_keys: Option<Vec<UserKey>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    linked_box_keys: Option<Vec<BoxKey>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Assignee {
+    email: String,
+}
+
+impl Update {
+    pub fn name(mut self, name: &str) -> Update {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn notes(mut self, notes: &str) -> Update {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    /// Move the Box to another Stage of its Pipeline.
+    pub fn stage_key(mut self, stage_key: &StageKey) -> Update {
+        self.stage_key = Some(stage_key.clone());
+        self
+    }
+
+    /// Replace the users the Box is assigned to, by email address.
+    pub fn assignees(mut self, emails: Vec<String>) -> Update {
+        self.assigned_to_sharing_entries = Some(emails.into_iter().map(|email| Assignee { email: email }).collect());
+        self
+    }
+
+    /// Replace the users following the Box.
+    pub fn followers(mut self, user_keys: Vec<UserKey>) -> Update {
+        self.follower_keys = Some(user_keys);
+        self
+    }
+
+    /// Replace the Boxes this Box is linked to.
+    pub fn linked_box_keys(mut self, box_keys: Vec<BoxKey>) -> Update {
+        self.linked_box_keys = Some(box_keys);
+        self
+    }
+
+    pub fn send(self, c: &Client) -> Result<Box, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Edit the Box without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Box> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Update {
+    type Response = Box;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn path(&self) -> String {
+        format!("boxes/{}", self.box_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::json(self)?))
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// Edit a Box, and get it back as it is afterwards
+///
+/// API docs:
+/// <https://www.streak.com/api/#editbox>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+///     let globex = streak.add_box(&pipeline.pipeline_key, "Globex", &pipeline.stage_order[0]);
+///     let client = streak.client();
+///
+///     let acme = streak::api::boxes::update(&acme.box_key)
+///         .notes("Wants a demo next week")
+///         .stage_key(&pipeline.stage_order[1])
+///         .assignees(vec!["sales@example.com".into()])
+///         .linked_box_keys(vec![globex.box_key.clone()])
+///         .send(&client)
+///         .expect("edit the box");
+///     assert_eq!(acme.name, "ACME");
+///     assert_eq!(acme.notes, Some("Wants a demo next week".into()));
+///     assert_eq!(acme.stage_key, pipeline.stage_order[1]);
+///     assert_eq!(acme.assigned_to_sharing_entries.len(), 1);
+///     assert_eq!(acme.linked_box_keys, vec![globex.box_key]);
+/// }
+/// ```
+pub fn update(box_key: &BoxKey) -> Update {
+    Update {
+        box_key: box_key.clone(),
+        .. Update::default()
+    }
+}
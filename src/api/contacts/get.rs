@@ -1,9 +1,37 @@
-use serde_json;
-
 use error::StreakError;
 use client::Client;
+use endpoint::{ApiVersion, Endpoint};
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::ContactKey;
 use super::Contact;
 
+/// The endpoint getting a specific Contact.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub key: ContactKey,
+}
+
+impl Get {
+    pub fn new(key: &ContactKey) -> Get {
+        Get {
+            key: key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = Contact;
+
+    fn version(&self) -> ApiVersion {
+        ApiVersion::V2
+    }
+
+    fn path(&self) -> String {
+        format!("contacts/{}", self.key)
+    }
+}
+
 /// Get a Contact
 ///
 /// API docs:
@@ -15,7 +43,7 @@ use super::Contact;
 /// use streak::Client;
 ///
 /// fn main() {
-///     let client = streak::Client::example();
+///     let client = streak::Client::example("contacts_get");
 ///     let pipelines = streak::api::pipelines::list(&client).expect("list pipelines");
 ///     let pipeline_key = &pipelines[0].pipeline_key;
 ///     let boxes = streak::api::boxes::list(&client, pipeline_key).expect("list boxes by pipeline");
@@ -26,8 +54,12 @@ use super::Contact;
 ///     assert!(contact.key != "");
 /// }
 /// ```
-pub fn get(client: &Client, key: &str) -> Result<Contact, StreakError> {
-    let res = client.get_v2(&format!("contacts/{}", key), ())?;
-    let result = serde_json::from_value(res.clone())?;
-    Ok(result)
+pub fn get(client: &Client, key: &ContactKey) -> Result<Contact, StreakError> {
+    client.execute(&Get::new(key))
+}
+
+/// Get a Contact without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, key: &ContactKey) -> StreakFuture<Contact> {
+    client.execute(&Get::new(key))
 }
@@ -1,19 +1,40 @@
 pub mod get;
-pub use self::get::get;
+pub use self::get::{get, Get};
+#[cfg(feature = "async")]
+pub use self::get::get_async;
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use api::{ContactKey, Model, Timestamp, UserKey};
+use api::model;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Contact {
+    #[serde(default)]
     pub team_key: String,
+    #[serde(default)]
     pub given_name: String,
+    #[serde(default)]
     pub family_name: String,
     pub title: Option<String>,
     pub email_addresses: Option<Vec<String>>,
     pub phone_numbers: Option<Vec<String>>,
-    pub last_saved_user_key: String,
-    pub creator_key: String,
-    pub creation_date: u64,
-    pub key: String,
-    pub version_timestamp: u64,
-    pub last_saved_timestamp: u64,
+    pub last_saved_user_key: UserKey,
+    pub creator_key: UserKey,
+    pub creation_date: Timestamp,
+    pub key: ContactKey,
+    pub version_timestamp: Timestamp,
+    pub last_saved_timestamp: Timestamp,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for Contact {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
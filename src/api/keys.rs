@@ -0,0 +1,112 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+/// Declare a key type, a `String` that can only be used where that kind of
+/// key is expected.
+macro_rules! key {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new<S: Into<String>>(key: S) -> $name {
+                $name(key.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(key: String) -> $name {
+                $name(key)
+            }
+        }
+
+        impl<'a> From<&'a str> for $name {
+            fn from(key: &'a str) -> $name {
+                $name(key.into())
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl<'a> PartialEq<&'a str> for $name {
+            fn eq(&self, other: &&'a str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+key! {
+    /// The key of a Pipeline.
+    ///
+    /// Keys of different kinds can not be mixed up:
+    ///
+    /// ```rust,compile_fail
+    /// extern crate streak;
+    ///
+    /// use streak::api::StageKey;
+    ///
+    /// fn main() {
+    ///     let client = streak::Client::new("api-key");
+    ///     let stage_key = StageKey::new("5001");
+    ///     streak::api::pipelines::get(&client, &stage_key);
+    /// }
+    /// ```
+    PipelineKey
+}
+
+key! {
+    /// The key of a Box.
+    BoxKey
+}
+
+key! {
+    /// The key of a Stage. It is only unique within its Pipeline.
+    StageKey
+}
+
+key! {
+    /// The key of a Contact.
+    ContactKey
+}
+
+key! {
+    /// The key of a user of Streak.
+    UserKey
+}
+
+key! {
+    /// The key of a comment on a Box.
+    CommentKey
+}
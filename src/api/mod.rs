@@ -2,3 +2,50 @@ pub mod pipelines;
 pub mod boxes;
 pub mod contacts;
 pub mod search;
+
+mod keys;
+pub use self::keys::{BoxKey, CommentKey, ContactKey, PipelineKey, StageKey, UserKey};
+
+mod timestamp;
+pub use self::timestamp::Timestamp;
+
+mod model;
+pub use self::model::Model;
+
+use error::StreakError;
+
+/// The answer to a request deleting something.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::{Client, StreakError};
+/// use streak::transport::{Method, MockTransport, StatusCode};
+///
+/// fn main() {
+///     let mock = MockTransport::new();
+///     mock.respond(Method::Delete, "/v1/pipelines/p", StatusCode::Ok, r#"{"success": false}"#);
+///     let client = Client::with_transport("api-key", mock);
+///
+///     match streak::api::pipelines::delete(&client, &"p".into()) {
+///         Err(StreakError::NotDeleted(resource)) => assert_eq!(resource, "pipelines/p"),
+///         other => panic!("expected the delete to fail, got {:?}", other),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deleted {
+    pub success: bool,
+}
+
+impl Deleted {
+    /// Fail with `NotDeleted` unless the service reported success in
+    /// deleting `resource`.
+    pub fn check(self, resource: String) -> Result<(), StreakError> {
+        if self.success {
+            Ok(())
+        } else {
+            Err(StreakError::NotDeleted(resource))
+        }
+    }
+}
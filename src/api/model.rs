@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use serde_json::Value;
+
+use error::StreakError;
+
+/// A resource read from the Streak API.
+///
+/// Models keep the properties of their JSON that this library does not know
+/// about in their `extra` map, so a property Streak adds does not stop them
+/// from being read. Those properties are the schema drift `unknown_fields`
+/// reports, and that `strict` turns into an error.
+///
+/// ```rust
+/// extern crate serde_json;
+/// extern crate streak;
+///
+/// use streak::StreakError;
+/// use streak::api::Model;
+/// use streak::api::pipelines::Stage;
+///
+/// fn main() {
+///     let stage: Stage = serde_json::from_str(r##"{
+///         "name": "Lead",
+///         "key": "5001",
+///         "color": {"foregroundColor": "#ffffff", "backgroundColor": "#3d85c6"},
+///         "boxCount": 2,
+///         "archived": false
+///     }"##).expect("read the stage");
+///     assert_eq!(stage.extra["archived"], false);
+///     assert_eq!(stage.unknown_fields(), vec!["archived"]);
+///
+///     match stage.strict() {
+///         Err(StreakError::SchemaDrift(fields)) => assert_eq!(fields, vec!["archived"]),
+///         other => panic!("expected schema drift, got {:?}", other),
+///     }
+/// }
+/// ```
+pub trait Model {
+    /// The unknown properties of the model and of the models inside it, as
+    /// paths such as `stages.5001.archived`.
+    fn unknown_fields(&self) -> Vec<String>;
+
+    /// The model itself if it has no unknown properties, or else a
+    /// `SchemaDrift` error listing them. Meant for tests that should fail
+    /// when the API changes.
+    fn strict(self) -> Result<Self, StreakError> where Self: Sized {
+        let unknown = self.unknown_fields();
+        if unknown.is_empty() {
+            Ok(self)
+        } else {
+            Err(StreakError::SchemaDrift(unknown))
+        }
+    }
+}
+
+impl<T: Model> Model for Vec<T> {
+    fn unknown_fields(&self) -> Vec<String> {
+        self.iter()
+            .enumerate()
+            .flat_map(|(i, model)| prefixed(&i, model))
+            .collect()
+    }
+}
+
+impl<T: Model> Model for Option<T> {
+    fn unknown_fields(&self) -> Vec<String> {
+        self.as_ref().map_or_else(Vec::new, |model| model.unknown_fields())
+    }
+}
+
+impl<K: Display + Eq + Hash + Ord, T: Model> Model for HashMap<K, T> {
+    fn unknown_fields(&self) -> Vec<String> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter()
+            .flat_map(|(key, model)| prefixed(key, model))
+            .collect()
+    }
+}
+
+/// The keys of `extra`, followed by the unknown fields of the models a model
+/// holds, each under the name of the property holding it.
+pub(crate) fn unknown_fields(extra: &HashMap<String, Value>, nested: &[(&str, &dyn Model)]) -> Vec<String> {
+    let mut fields: Vec<String> = extra.keys().cloned().collect();
+    fields.sort();
+    for &(name, model) in nested {
+        fields.extend(prefixed(&name, model));
+    }
+    fields
+}
+
+fn prefixed<P: Display + ?Sized>(prefix: &P, model: &dyn Model) -> Vec<String> {
+    model.unknown_fields()
+        .into_iter()
+        .map(|field| format!("{}.{}", prefix, field))
+        .collect()
+}
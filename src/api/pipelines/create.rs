@@ -0,0 +1,116 @@
+//! Pipeline actions
+
+use reqwest::Method;
+use serde::Serializer;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use super::Pipeline;
+
+/// The endpoint creating a Pipeline. Build it with `pipelines::create`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Create {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team_wide: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "comma_separated")]
+    stage_names: Option<Vec<String>>,
+}
+
+impl Create {
+    pub fn description(mut self, description: &str) -> Create {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Create the Pipeline in the team `team_key`.
+    pub fn team_key(mut self, team_key: &str) -> Create {
+        self.team_key = Some(team_key.into());
+        self
+    }
+
+    /// Share the Pipeline with everyone in its team.
+    pub fn team_wide(mut self, team_wide: bool) -> Create {
+        self.team_wide = Some(team_wide);
+        self
+    }
+
+    /// The stages of the new Pipeline, in order.
+    pub fn stage_names(mut self, stage_names: Vec<String>) -> Create {
+        self.stage_names = Some(stage_names);
+        self
+    }
+
+    pub fn send(self, c: &Client) -> Result<Pipeline, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Create the Pipeline without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Pipeline> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Create {
+    type Response = Pipeline;
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn path(&self) -> String {
+        "pipelines".into()
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::form(self)?))
+    }
+}
+
+fn comma_separated<S>(names: &Option<Vec<String>>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+{
+    match *names {
+        Some(ref names) => serializer.serialize_str(&names.join(",")),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Create a Pipeline
+///
+/// API docs:
+/// <https://www.streak.com/api/#createpipeline>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let client = streak.client();
+///     let pipeline = streak::api::pipelines::create("Partner Program")
+///         .description("Partners signed up this year")
+///         .stage_names(vec!["Applied".into(), "Onboarding".into(), "Active".into()])
+///         .send(&client)
+///         .expect("create a pipeline");
+///     assert_eq!(pipeline.name, "Partner Program");
+///     assert_eq!(pipeline.stage_order.len(), 3);
+///     assert_eq!(pipeline.stages[&pipeline.stage_order[0]].name, "Applied");
+/// }
+/// ```
+pub fn create(name: &str) -> Create {
+    Create {
+        name: name.into(),
+        .. Create::default()
+    }
+}
@@ -0,0 +1,75 @@
+//! Pipeline actions
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::PipelineKey;
+use api::Deleted;
+
+/// The endpoint deleting a Pipeline.
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub pipeline_key: PipelineKey,
+}
+
+impl Delete {
+    pub fn new(pipeline_key: &PipelineKey) -> Delete {
+        Delete {
+            pipeline_key: pipeline_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Delete {
+    type Response = Deleted;
+
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}", self.pipeline_key)
+    }
+}
+
+/// Delete a Pipeline, along with all of its Boxes
+///
+/// API docs:
+/// <https://www.streak.com/api/#deletepipeline>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::StreakError;
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     let client = streak.client();
+///
+///     streak::api::pipelines::delete(&client, &pipeline.pipeline_key).expect("delete the pipeline");
+///     match streak::api::pipelines::get(&client, &pipeline.pipeline_key) {
+///         Err(StreakError::NotFound { .. }) => {},
+///         other => panic!("expected the pipeline to be gone, got {:?}", other),
+///     }
+/// }
+/// ```
+pub fn delete(client: &Client, pipeline_key: &PipelineKey) -> Result<(), StreakError> {
+    let delete = Delete::new(pipeline_key);
+    client.execute(&delete)?.check(delete.path())
+}
+
+/// Delete a Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn delete_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakFuture<()> {
+    let delete = Delete::new(pipeline_key);
+    let resource = delete.path();
+    Box::new(client.execute(&delete).and_then(move |deleted| deleted.check(resource)))
+}
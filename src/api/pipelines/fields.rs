@@ -0,0 +1,408 @@
+//! Field actions
+//!
+//! Fields are the columns of a Pipeline that every Box has a value for, such
+//! as a deal size or a close date.
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::PipelineKey;
+use api::Deleted;
+use super::{Field, FieldItem, FieldItems, FieldSettings, FieldType, FormulaSettings};
+
+/// The endpoint listing the Fields of a Pipeline.
+#[derive(Debug, Clone)]
+pub struct List {
+    pub pipeline_key: PipelineKey,
+}
+
+impl List {
+    pub fn new(pipeline_key: &PipelineKey) -> List {
+        List {
+            pipeline_key: pipeline_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for List {
+    type Response = Vec<Field>;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/fields", self.pipeline_key)
+    }
+}
+
+/// The endpoint getting a specific Field.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub pipeline_key: PipelineKey,
+    pub field_key: String,
+}
+
+impl Get {
+    pub fn new(pipeline_key: &PipelineKey, field_key: &str) -> Get {
+        Get {
+            pipeline_key: pipeline_key.clone(),
+            field_key: field_key.into(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = Field;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/fields/{}", self.pipeline_key, self.field_key)
+    }
+}
+
+/// The endpoint creating a Field. Build it with `fields::create`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Create {
+    #[serde(skip)]
+    pipeline_key: PipelineKey,
+    name: String,
+    #[serde(rename = "type")]
+    field_type: FieldType,
+    #[serde(flatten)]
+    settings: Option<FieldSettings>,
+}
+
+impl Create {
+    /// The settings of the field. Sending fails with `InvalidFieldValue` when
+    /// they do not go with its type.
+    pub fn settings(mut self, settings: FieldSettings) -> Create {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// The choices of a `Dropdown` field, or the tags of a `Tag` field.
+    pub fn items(self, names: &[&str]) -> Create {
+        let items = FieldItems {
+            items: names.iter().map(|name| FieldItem::new(name)).collect(),
+        };
+        let settings = match self.field_type {
+            FieldType::Tag => FieldSettings::Tag(items),
+            _ => FieldSettings::Dropdown(items),
+        };
+        self.settings(settings)
+    }
+
+    /// The formula of a `Formula` field.
+    pub fn formula(self, formula: &str) -> Create {
+        self.settings(FieldSettings::Formula(FormulaSettings {
+            formula: formula.into(),
+        }))
+    }
+
+    pub fn send(self, c: &Client) -> Result<Field, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Create the Field without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Field> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Create {
+    type Response = Field;
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/fields", self.pipeline_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        match self.settings {
+            Some(ref settings) if settings.field_type() != self.field_type => {
+                Err(StreakError::InvalidFieldValue(format!(
+                    "{} is a {} field and does not take {} settings", self.name, self.field_type, settings.field_type())))
+            },
+            _ => Ok(Some(RequestBody::json(self)?)),
+        }
+    }
+}
+
+/// The endpoint editing a Field. Build it with `fields::update`.
+///
+/// The type of a Field can not be changed once it is created.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    #[serde(skip)]
+    pipeline_key: PipelineKey,
+    #[serde(skip)]
+    field_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(flatten)]
+    settings: Option<FieldSettings>,
+}
+
+impl Update {
+    pub fn name(mut self, name: &str) -> Update {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Replace the settings of the field. They must go with its type, which
+    /// can not be changed.
+    pub fn settings(mut self, settings: FieldSettings) -> Update {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Replace the choices of a `Dropdown` field. Items keep their key, so
+    /// boxes using them keep their value; items without a key are created.
+    pub fn dropdown_items(self, items: Vec<FieldItem>) -> Update {
+        self.settings(FieldSettings::Dropdown(FieldItems { items: items }))
+    }
+
+    /// Replace the tags of a `Tag` field, like `dropdown_items`.
+    pub fn tag_items(self, items: Vec<FieldItem>) -> Update {
+        self.settings(FieldSettings::Tag(FieldItems { items: items }))
+    }
+
+    /// Replace the formula of a `Formula` field.
+    pub fn formula(self, formula: &str) -> Update {
+        self.settings(FieldSettings::Formula(FormulaSettings {
+            formula: formula.into(),
+        }))
+    }
+
+    pub fn send(self, c: &Client) -> Result<Field, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Edit the Field without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Field> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Update {
+    type Response = Field;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/fields/{}", self.pipeline_key, self.field_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::json(self)?))
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// The endpoint deleting a Field.
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub pipeline_key: PipelineKey,
+    pub field_key: String,
+}
+
+impl Delete {
+    pub fn new(pipeline_key: &PipelineKey, field_key: &str) -> Delete {
+        Delete {
+            pipeline_key: pipeline_key.clone(),
+            field_key: field_key.into(),
+        }
+    }
+}
+
+impl Endpoint for Delete {
+    type Response = Deleted;
+
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/fields/{}", self.pipeline_key, self.field_key)
+    }
+}
+
+/// List the Fields of a Pipeline
+///
+/// API docs:
+/// <https://www.streak.com/api/#listfields>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::pipelines::{fields, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     streak.add_field(&pipeline.pipeline_key, "Close Date", FieldType::Date);
+///     let client = streak.client();
+///
+///     let fields = fields::list(&client, &pipeline.pipeline_key).expect("list fields");
+///     assert_eq!(fields[0].name, "Close Date");
+///     assert_eq!(fields[0].field_type, FieldType::Date);
+/// }
+/// ```
+pub fn list(client: &Client, pipeline_key: &PipelineKey) -> Result<Vec<Field>, StreakError> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// List the Fields of a Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn list_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakFuture<Vec<Field>> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// Get a specific Field
+///
+/// API docs:
+/// <https://www.streak.com/api/#getfield>
+pub fn get(client: &Client, pipeline_key: &PipelineKey, field_key: &str) -> Result<Field, StreakError> {
+    client.execute(&Get::new(pipeline_key, field_key))
+}
+
+/// Get a specific Field without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, pipeline_key: &PipelineKey, field_key: &str) -> StreakFuture<Field> {
+    client.execute(&Get::new(pipeline_key, field_key))
+}
+
+/// Create a Field
+///
+/// API docs:
+/// <https://www.streak.com/api/#createfield>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::StreakError;
+/// use streak::api::pipelines::{fields, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let region = fields::create(&pipeline.pipeline_key, "Region", FieldType::Dropdown)
+///         .items(&["EMEA", "APAC", "Americas"])
+///         .send(&client)
+///         .expect("create a field");
+///     assert_eq!(region.field_type, FieldType::Dropdown);
+///     assert_eq!(region.items().len(), 3);
+///     assert!(region.items().iter().all(|item| !item.key.is_empty()));
+///
+///     let notes = fields::create(&pipeline.pipeline_key, "Notes", FieldType::Text).items(&["short"]);
+///     match notes.send(&client) {
+///         Err(StreakError::InvalidFieldValue(_)) => {},
+///         other => panic!("expected the items to be refused, got {:?}", other),
+///     }
+///
+///     let fields = fields::list(&client, &pipeline.pipeline_key).expect("list fields");
+///     assert_eq!(fields.len(), 1);
+///     let field = fields::get(&client, &pipeline.pipeline_key, &region.key).expect("get the field");
+///     assert_eq!(field.name, "Region");
+/// }
+/// ```
+pub fn create(pipeline_key: &PipelineKey, name: &str, field_type: FieldType) -> Create {
+    Create {
+        pipeline_key: pipeline_key.clone(),
+        name: name.into(),
+        field_type: field_type,
+        settings: None,
+    }
+}
+
+/// Edit the name or the settings of a Field
+///
+/// API docs:
+/// <https://www.streak.com/api/#editfield>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::pipelines::{fields, FieldItem, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///     let labels = fields::create(&pipeline.pipeline_key, "Labels", FieldType::Tag)
+///         .items(&["hot"])
+///         .send(&client)
+///         .expect("create a field");
+///
+///     let mut tags = labels.items().to_vec();
+///     tags.push(FieldItem::new("cold"));
+///     let labels = fields::update(&pipeline.pipeline_key, &labels.key)
+///         .name("Temperature")
+///         .tag_items(tags)
+///         .send(&client)
+///         .expect("edit the field");
+///     assert_eq!(labels.name, "Temperature");
+///     assert!(labels.item("hot").is_some());
+///     assert!(labels.item("cold").is_some());
+/// }
+/// ```
+pub fn update(pipeline_key: &PipelineKey, field_key: &str) -> Update {
+    Update {
+        pipeline_key: pipeline_key.clone(),
+        field_key: field_key.into(),
+        .. Update::default()
+    }
+}
+
+/// Delete a Field, along with its value in every Box
+///
+/// API docs:
+/// <https://www.streak.com/api/#deletefield>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::pipelines::{fields, FieldType};
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     let field = streak.add_field(&pipeline.pipeline_key, "Notes", FieldType::Text);
+///     let client = streak.client();
+///
+///     fields::delete(&client, &pipeline.pipeline_key, &field.key).expect("delete the field");
+///     assert!(fields::list(&client, &pipeline.pipeline_key).expect("list fields").is_empty());
+/// }
+/// ```
+pub fn delete(client: &Client, pipeline_key: &PipelineKey, field_key: &str) -> Result<(), StreakError> {
+    let delete = Delete::new(pipeline_key, field_key);
+    client.execute(&delete)?.check(delete.path())
+}
+
+/// Delete a Field without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn delete_async(client: &AsyncClient, pipeline_key: &PipelineKey, field_key: &str) -> StreakFuture<()> {
+    let delete = Delete::new(pipeline_key, field_key);
+    let resource = delete.path();
+    Box::new(client.execute(&delete).and_then(move |deleted| deleted.check(resource)))
+}
@@ -1,11 +1,35 @@
 //! Pipeline actions
 
-use serde_json;
-
 use error::StreakError;
 use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::PipelineKey;
 use super::Pipeline;
 
+/// The endpoint getting a specific Pipeline.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub pipeline_key: PipelineKey,
+}
+
+impl Get {
+    pub fn new(pipeline_key: &PipelineKey) -> Get {
+        Get {
+            pipeline_key: pipeline_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = Pipeline;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}", self.pipeline_key)
+    }
+}
+
 /// Get a specific Pipeline
 ///
 /// API docs:
@@ -17,15 +41,19 @@ use super::Pipeline;
 /// use streak::Client;
 ///
 /// fn main() {
-///     let client = streak::Client::example();
+///     let client = streak::Client::example("pipelines_get");
 ///     let pipelines = streak::api::pipelines::list(&client).expect("list all pipelines");
 ///     let pipeline = streak::api::pipelines::get(&client, &pipelines[0].pipeline_key).expect("get a pipeline");
 ///     println!("pipeline {:?}", pipeline);
 ///     assert!(pipeline.fields.len() > 0);
 /// }
 /// ```
-pub fn get(client: &Client, pipeline_key: &str) -> Result<Pipeline, StreakError> {
-    let res = client.get(&format!("pipelines/{}", pipeline_key), ())?;
-    let pipeline = serde_json::from_value(res.clone())?;
-    Ok(pipeline)
+pub fn get(client: &Client, pipeline_key: &PipelineKey) -> Result<Pipeline, StreakError> {
+    client.execute(&Get::new(pipeline_key))
+}
+
+/// Get a specific Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakFuture<Pipeline> {
+    client.execute(&Get::new(pipeline_key))
 }
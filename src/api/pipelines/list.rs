@@ -1,11 +1,24 @@
 //! Pipeline actions
 
-use serde_json;
-
 use error::StreakError;
 use client::Client;
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
 use super::Pipeline;
 
+/// The endpoint listing all Pipelines.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct List;
+
+impl Endpoint for List {
+    type Response = Vec<Pipeline>;
+
+    fn path(&self) -> String {
+        "pipelines".into()
+    }
+}
+
 /// List all Pipelines
 ///
 /// API docs:
@@ -17,7 +30,7 @@ use super::Pipeline;
 /// use streak::Client;
 ///
 /// fn main() {
-///     let client = streak::Client::example();
+///     let client = streak::Client::example("pipelines_list");
 ///     let pipelines = streak::api::pipelines::list(&client).expect("list all pipelines");
 ///     println!("{:?}", pipelines);
 ///     assert!(pipelines.len() > 0);
@@ -25,7 +38,11 @@ use super::Pipeline;
 /// }
 /// ```
 pub fn list(client: &Client) -> Result<Vec<Pipeline>, StreakError> {
-    let res = client.get("pipelines", ())?;
-    let pipeline = serde_json::from_value(res.clone())?;
-    Ok(pipeline)
+    client.execute(&List)
+}
+
+/// List all Pipelines without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn list_async(client: &AsyncClient) -> StreakFuture<Vec<Pipeline>> {
+    client.execute(&List)
 }
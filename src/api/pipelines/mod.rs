@@ -1,35 +1,89 @@
 mod list;
-pub use self::list::list;
+pub use self::list::{list, List};
+#[cfg(feature = "async")]
+pub use self::list::list_async;
 
 mod get;
-pub use self::get::get;
+pub use self::get::{get, Get};
+#[cfg(feature = "async")]
+pub use self::get::get_async;
+
+mod create;
+pub use self::create::{create, Create};
+
+mod update;
+pub use self::update::{update, Update};
+
+mod delete;
+pub use self::delete::{delete, Delete};
+#[cfg(feature = "async")]
+pub use self::delete::delete_async;
+
+pub mod stages;
+pub mod fields;
 
 use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use api::{Model, PipelineKey, StageKey, Timestamp, UserKey};
+use api::model;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Pipeline {
-    pub key: String,
-    pub pipeline_key: String,
-    pub creator_key: String,
+    pub key: PipelineKey,
+    pub pipeline_key: PipelineKey,
+    pub creator_key: UserKey,
     pub name: String,
     pub description: Option<String>,
     pub org_wide: bool,
     pub fields: Vec<Field>,
-    pub stages: HashMap<String, Stage>,
-    pub stage_order: Vec<String>,
+    pub stages: HashMap<StageKey, Stage>,
+    pub stage_order: Vec<StageKey>,
     pub acl_entries: Vec<AclEntry>,
     pub owner: Option<AclEntry>,
     // Undocumented fields
-    pub team_key: String,
-    pub team_wide: bool,
-    pub creation_timestamp: i64,
-    pub last_updated_timestamp: i64,
-    pub last_saved_timestamp: i64,
-    pub box_count_hint: i32,
-    pub box_count: i32,
-    pub sharing_restricted_to_org: bool,
-    pub sharing_restricted_to_team: bool,
+    pub team_key: Option<String>,
+    pub team_wide: Option<bool>,
+    pub creation_timestamp: Option<Timestamp>,
+    pub last_updated_timestamp: Option<Timestamp>,
+    pub last_saved_timestamp: Option<Timestamp>,
+    pub box_count_hint: Option<i32>,
+    pub box_count: Option<i32>,
+    pub sharing_restricted_to_org: Option<bool>,
+    pub sharing_restricted_to_team: Option<bool>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for Pipeline {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[
+            ("fields", &self.fields),
+            ("stages", &self.stages),
+            ("aclEntries", &self.acl_entries),
+            ("owner", &self.owner),
+        ])
+    }
+}
+
+impl Pipeline {
+    /// The field with the key `field`, or else the one called `field`.
+    pub fn field(&self, field: &str) -> Option<&Field> {
+        self.fields.iter()
+            .find(|f| f.key == field)
+            .or_else(|| self.fields.iter().find(|f| f.name == field))
+    }
+
+    /// The Stage with the key `stage`, or else the one called `stage`.
+    pub fn stage(&self, stage: &str) -> Option<&Stage> {
+        self.stages.get(stage)
+            .or_else(|| self.stages.values().find(|s| s.name == stage))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,31 +92,194 @@ pub struct Field {
     pub name: String,
     pub key: String,
     #[serde(rename = "type")]
-    pub field_type: String,
-    pub last_updated_timestamp: Option<i64>,
+    pub field_type: FieldType,
+    pub last_updated_timestamp: Option<Timestamp>,
+    /// The choices of a `Dropdown` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dropdown_settings: Option<FieldItems>,
+    /// The tags of a `Tag` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_settings: Option<FieldItems>,
+    /// The formula of a `Formula` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula_settings: Option<FormulaSettings>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Model for Field {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
+}
+
+impl Field {
+    /// The dropdown choices or tags of the field, if it has any.
+    pub fn items(&self) -> &[FieldItem] {
+        let settings = match self.field_type {
+            FieldType::Dropdown => self.dropdown_settings.as_ref(),
+            FieldType::Tag => self.tag_settings.as_ref(),
+            _ => None,
+        };
+        settings.map(|s| s.items.as_slice()).unwrap_or(&[])
+    }
+
+    /// The dropdown choice or tag called `name`.
+    pub fn item(&self, name: &str) -> Option<&FieldItem> {
+        self.items().iter().find(|i| i.name == name)
+    }
+
+    /// The settings that go with the type of the field, if it has any.
+    pub fn settings(&self) -> Option<FieldSettings> {
+        match self.field_type {
+            FieldType::Dropdown => self.dropdown_settings.clone().map(FieldSettings::Dropdown),
+            FieldType::Tag => self.tag_settings.clone().map(FieldSettings::Tag),
+            FieldType::Formula => self.formula_settings.clone().map(FieldSettings::Formula),
+            _ => None,
+        }
+    }
+}
+
+/// The settings of a `Field` that depend on its type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum FieldSettings {
+    /// The choices of a `Dropdown` field.
+    #[serde(rename = "dropdownSettings")]
+    Dropdown(FieldItems),
+    /// The tags of a `Tag` field.
+    #[serde(rename = "tagSettings")]
+    Tag(FieldItems),
+    /// The formula of a `Formula` field.
+    #[serde(rename = "formulaSettings")]
+    Formula(FormulaSettings),
+}
+
+impl FieldSettings {
+    /// The type of the fields that take these settings.
+    pub fn field_type(&self) -> FieldType {
+        match *self {
+            FieldSettings::Dropdown(_) => FieldType::Dropdown,
+            FieldSettings::Tag(_) => FieldType::Tag,
+            FieldSettings::Formula(_) => FieldType::Formula,
+        }
+    }
+}
+
+/// The kind of value a `Field` holds.
+///
+/// Types this library does not know about yet are kept as `Unknown`, with
+/// the name the service used for them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    Text,
+    Date,
+    Person,
+    Checkbox,
+    Dropdown,
+    Tag,
+    Formula,
+    Unknown(String),
+}
+
+impl FieldType {
+    /// The name of the type in the Streak API, such as `TEXT_INPUT`.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            FieldType::Text => "TEXT_INPUT",
+            FieldType::Date => "DATE",
+            FieldType::Person => "PERSON",
+            FieldType::Checkbox => "CHECKBOX",
+            FieldType::Dropdown => "DROPDOWN",
+            FieldType::Tag => "TAG",
+            FieldType::Formula => "FORMULA",
+            FieldType::Unknown(ref name) => name,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FieldType {
+    fn from(name: &'a str) -> FieldType {
+        match name {
+            "TEXT_INPUT" => FieldType::Text,
+            "DATE" => FieldType::Date,
+            "PERSON" => FieldType::Person,
+            "CHECKBOX" => FieldType::Checkbox,
+            "DROPDOWN" => FieldType::Dropdown,
+            "TAG" => FieldType::Tag,
+            "FORMULA" => FieldType::Formula,
+            _ => FieldType::Unknown(name.into()),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for FieldType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FieldType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(FieldType::from(name.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FieldItems {
     pub items: Vec<FieldItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FieldItem {
+    /// Empty for items that have not been created yet.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub key: String,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+impl FieldItem {
+    /// An item to be created, which the service will give a key.
+    pub fn new(name: &str) -> FieldItem {
+        FieldItem {
+            key: String::new(),
+            name: name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaSettings {
+    pub formula: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Stage {
     pub name: String,
-    pub key: String,
+    pub key: StageKey,
     pub color: Color,
+    #[serde(default)]
     pub box_count: i32,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for Stage {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -80,6 +297,15 @@ pub struct AclEntry {
     pub is_owner: Option<bool>, // deprecated?
     pub image: String,
     pub display_name: String,
-    pub user_key: String,
+    pub user_key: UserKey,
     pub permission_set_name: String,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for AclEntry {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
@@ -0,0 +1,374 @@
+//! Stage actions
+//!
+//! Stages are the columns of a Pipeline. Every Box is in exactly one of them.
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{PipelineKey, StageKey};
+use api::Deleted;
+use super::{Color, Pipeline, Stage};
+
+/// The endpoint listing the Stages of a Pipeline.
+#[derive(Debug, Clone)]
+pub struct List {
+    pub pipeline_key: PipelineKey,
+}
+
+impl List {
+    pub fn new(pipeline_key: &PipelineKey) -> List {
+        List {
+            pipeline_key: pipeline_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for List {
+    type Response = HashMap<StageKey, Stage>;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/stages", self.pipeline_key)
+    }
+}
+
+/// The endpoint getting a specific Stage.
+#[derive(Debug, Clone)]
+pub struct Get {
+    pub pipeline_key: PipelineKey,
+    pub stage_key: StageKey,
+}
+
+impl Get {
+    pub fn new(pipeline_key: &PipelineKey, stage_key: &StageKey) -> Get {
+        Get {
+            pipeline_key: pipeline_key.clone(),
+            stage_key: stage_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Get {
+    type Response = Stage;
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/stages/{}", self.pipeline_key, self.stage_key)
+    }
+}
+
+/// The endpoint creating a Stage at the end of a Pipeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct Create {
+    #[serde(skip)]
+    pub pipeline_key: PipelineKey,
+    pub name: String,
+}
+
+impl Create {
+    pub fn new(pipeline_key: &PipelineKey, name: &str) -> Create {
+        Create {
+            pipeline_key: pipeline_key.clone(),
+            name: name.into(),
+        }
+    }
+}
+
+impl Endpoint for Create {
+    type Response = Stage;
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/stages", self.pipeline_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::form(self)?))
+    }
+}
+
+/// The endpoint editing a Stage. Build it with `stages::update`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Update {
+    #[serde(skip)]
+    pipeline_key: PipelineKey,
+    #[serde(skip)]
+    stage_key: StageKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<Color>,
+}
+
+impl Update {
+    pub fn name(mut self, name: &str) -> Update {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Update {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn send(self, c: &Client) -> Result<Stage, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Edit the Stage without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Stage> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Update {
+    type Response = Stage;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/stages/{}", self.pipeline_key, self.stage_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::json(self)?))
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// The endpoint deleting a Stage.
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub pipeline_key: PipelineKey,
+    pub stage_key: StageKey,
+}
+
+impl Delete {
+    pub fn new(pipeline_key: &PipelineKey, stage_key: &StageKey) -> Delete {
+        Delete {
+            pipeline_key: pipeline_key.clone(),
+            stage_key: stage_key.clone(),
+        }
+    }
+}
+
+impl Endpoint for Delete {
+    type Response = Deleted;
+
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}/stages/{}", self.pipeline_key, self.stage_key)
+    }
+}
+
+/// List the Stages of a Pipeline, by key
+///
+/// API docs:
+/// <https://www.streak.com/api/#liststages>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let stages = streak::api::pipelines::stages::list(&client, &pipeline.pipeline_key).expect("list stages");
+///     assert_eq!(stages.len(), 2);
+///     assert_eq!(stages[&pipeline.stage_order[1]].name, "Won");
+/// }
+/// ```
+pub fn list(client: &Client, pipeline_key: &PipelineKey) -> Result<HashMap<StageKey, Stage>, StreakError> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// List the Stages of a Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn list_async(client: &AsyncClient, pipeline_key: &PipelineKey) -> StreakFuture<HashMap<StageKey, Stage>> {
+    client.execute(&List::new(pipeline_key))
+}
+
+/// Get a specific Stage
+///
+/// API docs:
+/// <https://www.streak.com/api/#getstage>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let stage = streak::api::pipelines::stages::get(&client, &pipeline.pipeline_key, &pipeline.stage_order[0])
+///         .expect("get a stage");
+///     assert_eq!(stage.name, "Lead");
+/// }
+/// ```
+pub fn get(client: &Client, pipeline_key: &PipelineKey, stage_key: &StageKey) -> Result<Stage, StreakError> {
+    client.execute(&Get::new(pipeline_key, stage_key))
+}
+
+/// Get a specific Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn get_async(client: &AsyncClient, pipeline_key: &PipelineKey, stage_key: &StageKey) -> StreakFuture<Stage> {
+    client.execute(&Get::new(pipeline_key, stage_key))
+}
+
+/// Create a Stage at the end of a Pipeline
+///
+/// API docs:
+/// <https://www.streak.com/api/#createstage>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let lost = streak::api::pipelines::stages::create(&client, &pipeline.pipeline_key, "Lost")
+///         .expect("create a stage");
+///     let pipeline = streak::api::pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///     assert_eq!(pipeline.stage_order.last(), Some(&lost.key));
+/// }
+/// ```
+pub fn create(client: &Client, pipeline_key: &PipelineKey, name: &str) -> Result<Stage, StreakError> {
+    client.execute(&Create::new(pipeline_key, name))
+}
+
+/// Create a Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn create_async(client: &AsyncClient, pipeline_key: &PipelineKey, name: &str) -> StreakFuture<Stage> {
+    client.execute(&Create::new(pipeline_key, name))
+}
+
+/// Edit the name or the color of a Stage
+///
+/// API docs:
+/// <https://www.streak.com/api/#editstage>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::pipelines::Color;
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let color = Color {
+///         foreground_color: "#000000".into(),
+///         background_color: "#ffe599".into(),
+///     };
+///     let stage = streak::api::pipelines::stages::update(&pipeline.pipeline_key, &pipeline.stage_order[0])
+///         .name("Qualified Lead")
+///         .color(color.clone())
+///         .send(&client)
+///         .expect("edit the stage");
+///     assert_eq!(stage.name, "Qualified Lead");
+///     assert_eq!(stage.color, color);
+/// }
+/// ```
+pub fn update(pipeline_key: &PipelineKey, stage_key: &StageKey) -> Update {
+    Update {
+        pipeline_key: pipeline_key.clone(),
+        stage_key: stage_key.clone(),
+        .. Update::default()
+    }
+}
+
+/// Delete a Stage. The Stage must not hold any Boxes.
+///
+/// API docs:
+/// <https://www.streak.com/api/#deletestage>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     streak::api::pipelines::stages::delete(&client, &pipeline.pipeline_key, &pipeline.stage_order[1])
+///         .expect("delete a stage");
+///     let pipeline = streak::api::pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///     assert_eq!(pipeline.stages.len(), 1);
+///     assert_eq!(pipeline.stage_order.len(), 1);
+/// }
+/// ```
+pub fn delete(client: &Client, pipeline_key: &PipelineKey, stage_key: &StageKey) -> Result<(), StreakError> {
+    let delete = Delete::new(pipeline_key, stage_key);
+    client.execute(&delete)?.check(delete.path())
+}
+
+/// Delete a Stage without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn delete_async(client: &AsyncClient, pipeline_key: &PipelineKey, stage_key: &StageKey) -> StreakFuture<()> {
+    let delete = Delete::new(pipeline_key, stage_key);
+    let resource = delete.path();
+    Box::new(client.execute(&delete).and_then(move |deleted| deleted.check(resource)))
+}
+
+/// Put the Stages of a Pipeline in a new order
+///
+/// `stage_order` must hold every stage key of the Pipeline exactly once. This
+/// is a shortcut for `pipelines::update(pipeline_key).stage_order(stage_order)`.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Won", "Lead"]);
+///     let client = streak.client();
+///
+///     let order = vec![pipeline.stage_order[1].clone(), pipeline.stage_order[0].clone()];
+///     let pipeline = streak::api::pipelines::stages::reorder(&client, &pipeline.pipeline_key, order.clone())
+///         .expect("reorder the stages");
+///     assert_eq!(pipeline.stage_order, order);
+/// }
+/// ```
+pub fn reorder(client: &Client, pipeline_key: &PipelineKey, stage_order: Vec<StageKey>) -> Result<Pipeline, StreakError> {
+    super::update(pipeline_key).stage_order(stage_order).send(client)
+}
+
+/// Reorder the Stages of a Pipeline without blocking the current thread.
+#[cfg(feature = "async")]
+pub fn reorder_async(client: &AsyncClient, pipeline_key: &PipelineKey, stage_order: Vec<StageKey>) -> StreakFuture<Pipeline> {
+    super::update(pipeline_key).stage_order(stage_order).send_async(client)
+}
@@ -0,0 +1,128 @@
+//! Pipeline actions
+
+use reqwest::Method;
+
+use error::StreakError;
+use client::{Client, RequestBody};
+use endpoint::Endpoint;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture};
+use api::{PipelineKey, StageKey};
+use super::Pipeline;
+
+/// The endpoint editing a Pipeline. Build it with `pipelines::update`.
+///
+/// Only the properties that were set are sent, everything else is left as it
+/// is.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    #[serde(skip)]
+    pipeline_key: PipelineKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team_wide: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sharing_restricted_to_team: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stage_order: Option<Vec<StageKey>>,
+}
+
+impl Update {
+    pub fn name(mut self, name: &str) -> Update {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Update {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Share the Pipeline with everyone in its team.
+    pub fn team_wide(mut self, team_wide: bool) -> Update {
+        self.team_wide = Some(team_wide);
+        self
+    }
+
+    /// Keep the Pipeline from being shared outside its team.
+    pub fn sharing_restricted_to_team(mut self, restricted: bool) -> Update {
+        self.sharing_restricted_to_team = Some(restricted);
+        self
+    }
+
+    /// Reorder the stages. `stage_order` must hold every stage key of the
+    /// Pipeline exactly once.
+    pub fn stage_order(mut self, stage_order: Vec<StageKey>) -> Update {
+        self.stage_order = Some(stage_order);
+        self
+    }
+
+    pub fn send(self, c: &Client) -> Result<Pipeline, StreakError> {
+        c.execute(&self)
+    }
+
+    /// Edit the Pipeline without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<Pipeline> {
+        c.execute(&self)
+    }
+}
+
+impl Endpoint for Update {
+    type Response = Pipeline;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn path(&self) -> String {
+        format!("pipelines/{}", self.pipeline_key)
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(Some(RequestBody::json(self)?))
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// Edit a Pipeline
+///
+/// API docs:
+/// <https://www.streak.com/api/#editpipeline>
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+///     let client = streak.client();
+///
+///     let mut stage_order = pipeline.stage_order.clone();
+///     stage_order.reverse();
+///     let pipeline = streak::api::pipelines::update(&pipeline.pipeline_key)
+///         .name("Enterprise Sales")
+///         .team_wide(true)
+///         .stage_order(stage_order.clone())
+///         .send(&client)
+///         .expect("edit the pipeline");
+///     assert_eq!(pipeline.name, "Enterprise Sales");
+///     assert_eq!(pipeline.team_wide, Some(true));
+///     assert_eq!(pipeline.stage_order, stage_order);
+/// }
+/// ```
+pub fn update(pipeline_key: &PipelineKey) -> Update {
+    Update {
+        pipeline_key: pipeline_key.clone(),
+        .. Update::default()
+    }
+}
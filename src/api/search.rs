@@ -1,18 +1,31 @@
 //! The Search endpoint allows you to search the contents on boxes. The search functionality returns a relevance sorted list of boxes.
 
-use serde_json;
+use std::collections::HashMap;
+
+use serde_json::Value;
+use serde_url_params;
 
 use client::Client;
+use endpoint::Endpoint;
 use error::StreakError;
+#[cfg(feature = "async")]
+use futures::Future;
+#[cfg(feature = "async")]
+use async_client::{AsyncClient, StreakFuture, StreakStream};
+#[cfg(feature = "async")]
+use pagination;
+use pagination::{Page, Paginated};
+use api::{BoxKey, ContactKey, Model, PipelineKey, StageKey, Timestamp};
+use api::model;
 
-#[derive(Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchParamsBuilder {
     query: Option<String>,
     name: Option<String>,
     page: Option<i32>,
-    pipeline_key: Option<Vec<String>>,
-    stage_key: Option<Vec<String>>,
+    pipeline_key: Option<Vec<PipelineKey>>,
+    stage_key: Option<Vec<StageKey>>,
 }
 
 impl SearchParamsBuilder {
@@ -21,20 +34,127 @@ impl SearchParamsBuilder {
         self
     }
 
-    pub fn pipeline_key(mut self, pipeline_key: Vec<String>) -> SearchParamsBuilder {
+    pub fn pipeline_key(mut self, pipeline_key: Vec<PipelineKey>) -> SearchParamsBuilder {
         self.pipeline_key = Some(pipeline_key);
         self
     }
 
-    pub fn stage_key(mut self, stage_key: Vec<String>) -> SearchParamsBuilder {
+    pub fn stage_key(mut self, stage_key: Vec<StageKey>) -> SearchParamsBuilder {
         self.stage_key = Some(stage_key);
         self
     }
 
     pub fn send(self, c: &Client) -> Result<SearchResponse, StreakError> {
-        let res = c.get("search", self)?;
-        let s_res = serde_json::from_value(res.clone())?;
-        Ok(s_res)
+        c.execute(&self)
+    }
+
+    /// Run the search without blocking the current thread.
+    #[cfg(feature = "async")]
+    pub fn send_async(self, c: &AsyncClient) -> StreakFuture<SearchResponse> {
+        c.execute(&self)
+    }
+
+    /// Iterate over the matching Boxes, following the pages of results from
+    /// the one set with `page`, or else the first. The results end with the
+    /// first page that is empty, or that is not the page asked for.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use streak::api::search;
+    /// use streak::testing::FakeStreak;
+    ///
+    /// fn main() {
+    ///     let streak = FakeStreak::start().expect("to start the fake service");
+    ///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+    ///     for i in 0..20 {
+    ///         streak.add_box(&pipeline.pipeline_key, &format!("ACME {}", i), &pipeline.stage_order[0]);
+    ///     }
+    ///     // Answer with pages of 7, and for pages past the end with the last
+    ///     // page instead of an empty one.
+    ///     streak.state().search_page_size = Some(7);
+    ///     streak.state().repeat_last_search_page = true;
+    ///     let client = streak.client();
+    ///
+    ///     let boxes = search::query("acme").iter(&client)
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .expect("search every page");
+    ///     assert_eq!(boxes.len(), 20);
+    /// }
+    /// ```
+    pub fn iter<'a>(self, c: &'a Client) -> Paginated<'a, BoxHandle> {
+        self.paginated(c, |res| res.results.boxes)
+    }
+
+    /// Iterate over the matching Contacts, following the pages of results
+    /// like `iter`.
+    pub fn contacts<'a>(self, c: &'a Client) -> Paginated<'a, ContactHandle> {
+        self.paginated(c, |res| res.results.contacts.unwrap_or_default())
+    }
+
+    /// Stream the matching Boxes, following the pages of results like `iter`.
+    #[cfg(feature = "async")]
+    pub fn iter_async(self, c: &AsyncClient) -> StreakStream<BoxHandle> {
+        self.paginated_async(c, |res| res.results.boxes)
+    }
+
+    /// Stream the matching Contacts, following the pages of results like
+    /// `iter`.
+    #[cfg(feature = "async")]
+    pub fn contacts_async(self, c: &AsyncClient) -> StreakStream<ContactHandle> {
+        self.paginated_async(c, |res| res.results.contacts.unwrap_or_default())
+    }
+
+    fn paginated<'a, T, F>(self, c: &'a Client, items: F) -> Paginated<'a, T>
+        where F: Fn(SearchResponse) -> Vec<T> + 'a
+    {
+        let first = self.page.unwrap_or(0);
+        Paginated::new(move |n| {
+            let page = first + n as i32;
+            let res = c.execute(&self.clone().page(page))?;
+            Ok(results_page(page, res, &items))
+        })
+    }
+
+    #[cfg(feature = "async")]
+    fn paginated_async<T, F>(self, c: &AsyncClient, items: F) -> StreakStream<T>
+        where T: 'static,
+              F: Fn(SearchResponse) -> Vec<T> + Clone + 'static
+    {
+        let c = c.clone();
+        let first = self.page.unwrap_or(0);
+        pagination::stream(move |n| {
+            let items = items.clone();
+            let page = first + n as i32;
+            c.execute(&self.clone().page(page)).map(move |res| results_page(page, res, &items))
+        })
+    }
+}
+
+/// The results of one kind on the page `asked` for. Past the last page the
+/// service may answer with another page, whose results were seen already.
+fn results_page<T, F>(asked: i32, res: SearchResponse, items: &F) -> Page<T>
+    where F: Fn(SearchResponse) -> Vec<T>
+{
+    if res.page != asked {
+        return Page { items: Vec::new(), more: false };
+    }
+    let items = items(res);
+    Page {
+        more: !items.is_empty(),
+        items: items,
+    }
+}
+
+impl Endpoint for SearchParamsBuilder {
+    type Response = SearchResponse;
+
+    fn path(&self) -> String {
+        "search".into()
+    }
+
+    fn query(&self) -> Result<String, StreakError> {
+        Ok(serde_url_params::to_string(self)?)
     }
 }
 
@@ -44,39 +164,90 @@ pub struct SearchResponse {
     pub results: SearchResults,
     pub page: i32,
     pub query: Option<String>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for SearchResponse {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[("results", &self.results)])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResults {
     pub orgs: Option<Vec<OrganizationHandle>>,
+    #[serde(default)]
     pub boxes: Vec<BoxHandle>,
     pub contacts: Option<Vec<ContactHandle>>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for SearchResults {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[
+            ("orgs", &self.orgs),
+            ("boxes", &self.boxes),
+            ("contacts", &self.contacts),
+        ])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrganizationHandle {
     pub name: String,
     pub key: String,
-    pub industry: String,
+    pub industry: Option<String>,
+    #[serde(default)]
     pub domains: Vec<String>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for OrganizationHandle {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BoxHandle {
-    pub box_key: String,
+    pub box_key: BoxKey,
     pub name: String,
-    pub last_updated_timestamp: i64, // TODO: Convert to DateTime
-    pub stage_key: String,
-    pub pipeline_key: String,
+    pub last_updated_timestamp: Timestamp,
+    pub stage_key: StageKey,
+    pub pipeline_key: PipelineKey,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for BoxHandle {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContactHandle {
-    pub key: String,
+    pub key: ContactKey,
     pub email_addresses: Option<Vec<String>>,
     pub title: Option<String>,
+    /// Properties this library does not know about.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Model for ContactHandle {
+    fn unknown_fields(&self) -> Vec<String> {
+        model::unknown_fields(&self.extra, &[])
+    }
 }
 
 /// Searching for boxes, contacts, and organizations by query
@@ -96,10 +267,32 @@ pub struct ContactHandle {
 /// }
 ///
 /// fn run_query() -> Result<SearchResponse, StreakError> {
-///     let c = streak::Client::example();
+///     let c = streak::Client::example("search_query");
 ///     search::query("test").send(&c)
 /// }
 /// ```
+///
+/// Follow every page of results with `iter`:
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::testing::FakeStreak;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+///     for i in 0..120 {
+///         streak.add_box(&pipeline.pipeline_key, &format!("ACME {}", i), &pipeline.stage_order[0]);
+///     }
+///     let client = streak.client();
+///
+///     let boxes = streak::api::search::query("acme").iter(&client)
+///         .collect::<Result<Vec<_>, _>>()
+///         .expect("search every page");
+///     assert_eq!(boxes.len(), 120);
+/// }
+/// ```
 pub fn query(query: &str) -> SearchParamsBuilder {
     SearchParamsBuilder {
         query: Some(query.into()),
@@ -124,7 +317,7 @@ pub fn query(query: &str) -> SearchParamsBuilder {
 /// }
 ///
 /// fn run_name() -> Result<SearchResponse, StreakError> {
-///     let c = streak::Client::example();
+///     let c = streak::Client::example("search_name");
 ///     search::name("AWS").send(&c)
 /// }
 /// ```
@@ -0,0 +1,136 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+
+/// A point in time, kept as the milliseconds since the Unix epoch that the
+/// Streak API uses for dates and timestamps.
+///
+/// It is serialized as that number of milliseconds. With the `chrono`
+/// feature it also converts to and from `chrono::DateTime<Utc>`. The API
+/// may send any number, so `to_datetime` fails on dates `chrono` can not
+/// represent instead of panicking.
+///
+/// ```rust
+/// extern crate serde_json;
+/// extern crate streak;
+///
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use streak::api::Timestamp;
+///
+/// fn main() {
+///     let t = Timestamp::from_millis(1_500_000_000_123);
+///     let time = t.to_system_time().expect("a time the platform can represent");
+///     assert_eq!(time, UNIX_EPOCH + Duration::from_millis(1_500_000_000_123));
+///     assert_eq!(Timestamp::from(time), t);
+///     assert_eq!(Timestamp::from(UNIX_EPOCH - Duration::from_millis(1)), Timestamp::from_millis(-1));
+///
+///     let earliest = Timestamp::from_millis(i64::min_value());
+///     if let Some(time) = earliest.to_system_time() {
+///         assert_eq!(Timestamp::from(time), earliest);
+///     }
+///     assert_eq!(serde_json::to_string(&t).unwrap(), "1500000000123");
+///     assert_eq!(serde_json::from_str::<Timestamp>("1500000000123").unwrap(), t);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_millis(millis: i64) -> Timestamp {
+        Timestamp(millis)
+    }
+
+    /// The current time.
+    pub fn now() -> Timestamp {
+        Timestamp::from(SystemTime::now())
+    }
+
+    /// The milliseconds since the Unix epoch, negative before it.
+    pub fn millis(&self) -> i64 {
+        self.0
+    }
+
+    /// The time as a `SystemTime`, or `None` when it is outside the range
+    /// the platform can represent.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        match self.0.checked_neg() {
+            Some(millis) if millis > 0 => UNIX_EPOCH.checked_sub(Duration::from_millis(millis as u64)),
+            Some(_) => UNIX_EPOCH.checked_add(Duration::from_millis(self.0 as u64)),
+            // i64::min_value() has no positive counterpart.
+            None => UNIX_EPOCH.checked_sub(Duration::from_millis(i64::max_value() as u64 + 1)),
+        }
+    }
+
+    /// The time as a UTC date and time, to the millisecond, or `None` when
+    /// it is outside the range `chrono` can represent.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use streak::api::Timestamp;
+    ///
+    /// fn main() {
+    ///     let t = Timestamp::from_millis(1_500_000_000_123);
+    ///     let datetime = t.to_datetime().expect("a date chrono can represent");
+    ///     assert_eq!(datetime.to_rfc3339(), "2017-07-14T02:40:00.123+00:00");
+    ///     assert_eq!(Timestamp::from(datetime), t);
+    ///
+    ///     let before_epoch = Timestamp::from_millis(-1).to_datetime().expect("a date before the epoch");
+    ///     assert_eq!(before_epoch.to_rfc3339(), "1969-12-31T23:59:59.999+00:00");
+    ///     assert_eq!(Timestamp::from_millis(i64::max_value()).to_datetime(), None);
+    /// }
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        // Round down to whole seconds, so that the milliseconds left over are
+        // never negative.
+        let mut secs = self.0 / 1000;
+        let mut millis = self.0 % 1000;
+        if millis < 0 {
+            secs -= 1;
+            millis += 1000;
+        }
+        Utc.timestamp_opt(secs, millis as u32 * 1_000_000).single()
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    /// Times too far from the epoch for an `i64` of milliseconds are clamped
+    /// to the earliest or latest `Timestamp`.
+    fn from(time: SystemTime) -> Timestamp {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => match to_millis(d) {
+                Some(millis) if millis <= i64::max_value() as u64 => Timestamp(millis as i64),
+                _ => Timestamp(i64::max_value()),
+            },
+            Err(e) => match to_millis(e.duration()) {
+                Some(millis) if millis <= i64::max_value() as u64 => Timestamp(-(millis as i64)),
+                _ => Timestamp(i64::min_value()),
+            },
+        }
+    }
+}
+
+/// The whole milliseconds in `d`, or `None` when they do not fit a `u64`.
+fn to_millis(d: Duration) -> Option<u64> {
+    d.as_secs()
+        .checked_mul(1000)
+        .and_then(|millis| millis.checked_add(u64::from(d.subsec_nanos() / 1_000_000)))
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(time: DateTime<Utc>) -> Timestamp {
+        // A leap second is reported as nanoseconds past 999_999_999.
+        let millis = ::std::cmp::min(time.nanosecond() / 1_000_000, 999);
+        Timestamp(time.timestamp() * 1000 + i64::from(millis))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
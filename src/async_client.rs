@@ -0,0 +1,330 @@
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+use futures::{Async, Future, Poll, Stream};
+use futures::future::{self, Loop};
+use reqwest::{self, StatusCode, Method, Url};
+use reqwest::header::Headers;
+use reqwest::unstable::async::{Client as ReqwestClient, Response};
+use serde;
+use serde_json::{self, Value};
+use tokio_core::reactor::{Handle, Timeout};
+
+use client::{self, RequestBody};
+use endpoint::Endpoint;
+use error::StreakError;
+use retry::RetryPolicy;
+use transport::{HttpRequest, Transport};
+
+/// A future resolving to a value from the Streak API.
+pub type StreakFuture<T> = Box<dyn Future<Item = T, Error = StreakError>>;
+
+/// A stream of values from the Streak API, such as the items of a listing
+/// that spans several pages.
+pub type StreakStream<T> = Box<dyn Stream<Item = T, Error = StreakError>>;
+
+/// The asynchronous Streak API Rust client.
+///
+/// Requests are driven by a `tokio_core` reactor, so many of them can be in
+/// flight at once without tying up a thread each. Failed requests are retried
+/// with the same policies as the blocking `Client`.
+///
+/// ```rust
+/// extern crate futures;
+/// extern crate streak;
+/// extern crate tokio_core;
+///
+/// use futures::future;
+/// use streak::testing::FakeStreak;
+/// use tokio_core::reactor::Core;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let sales = streak.add_pipeline("Sales", &["Lead"]);
+///     let hiring = streak.add_pipeline("Hiring", &["Applied"]);
+///     streak.add_box(&sales.pipeline_key, "ACME", &sales.stage_order[0]);
+///     streak.add_box(&hiring.pipeline_key, "Ann", &hiring.stage_order[0]);
+///     streak.add_box(&hiring.pipeline_key, "Bob", &hiring.stage_order[0]);
+///
+///     let mut core = Core::new().expect("to create a reactor");
+///     let client = streak.async_client(&core.handle());
+///
+///     let pipelines = core.run(streak::api::pipelines::list_async(&client)).expect("list all pipelines");
+///     let boxes = pipelines.iter()
+///         .map(|p| streak::api::boxes::list_async(&client, &p.pipeline_key))
+///         .collect::<Vec<_>>();
+///     let boxes = core.run(future::join_all(boxes)).expect("list boxes of every pipeline");
+///     assert_eq!(boxes.iter().map(|b| b.len()).collect::<Vec<_>>(), vec![1, 2]);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct AsyncClient {
+    /// Configure the client to send a request up to `retry_count` times in
+    /// total when it fails with a transient error, such as the service being
+    /// unavailable. Ignored when a `retry_policy` is set.
+    pub retry_count: u8,
+
+    /// Milliseconds to wait before the first retry. Later retries wait
+    /// longer. Ignored when a `retry_policy` is set.
+    pub retry_wait: u16,
+
+    pub(crate) api_url: String,
+    api_key: String,
+    pub(crate) headers: Headers,
+    pub(crate) retry_policy: Option<Arc<dyn RetryPolicy>>,
+    handle: Handle,
+    reqwest: ReqwestClient,
+    pub(crate) transport: Option<Arc<dyn Transport>>,
+}
+
+impl fmt::Debug for AsyncClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AsyncClient")
+            .field("retry_count", &self.retry_count)
+            .field("retry_wait", &self.retry_wait)
+            .field("api_url", &self.api_url)
+            .field("retry_policy", &self.retry_policy)
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+impl AsyncClient {
+    /// Create a new asynchronous client to the Streak service, running on the
+    /// reactor behind `handle`.
+    ///
+    /// Example:
+    ///
+    /// ```rust,no_run
+    /// extern crate streak;
+    /// extern crate tokio_core;
+    ///
+    /// use std::env;
+    /// use streak::AsyncClient;
+    /// use tokio_core::reactor::Core;
+    ///
+    /// fn main() {
+    ///     let core = Core::new().expect("to create a reactor");
+    ///     let api_key = env::var("STREAK_API_KEY").expect("to have STREAK_API_KEY set");
+    ///     let client = AsyncClient::new(&api_key, &core.handle());
+    /// }
+    /// ```
+    pub fn new(api_key: &str, handle: &Handle) -> AsyncClient {
+        AsyncClient {
+            retry_count: client::RETRY_COUNT,
+            retry_wait: client::RETRY_WAIT,
+            api_url: client::API_URL.into(),
+            api_key: api_key.into(),
+            headers: client::default_headers(),
+            retry_policy: None,
+            handle: handle.clone(),
+            reqwest: ReqwestClient::new(handle),
+            transport: None,
+        }
+    }
+
+    /// Create a client that sends its requests through `transport`, such as
+    /// a `transport::MockTransport`, instead of the asynchronous `reqwest`
+    /// client. A `Transport` blocks while it sends a request, and with it the
+    /// reactor, so this is meant for tests rather than for many requests at
+    /// once.
+    pub fn with_transport<T>(api_key: &str, handle: &Handle, transport: T) -> AsyncClient
+        where T: Transport + 'static
+    {
+        let mut client = AsyncClient::new(api_key, handle);
+        client.transport = Some(Arc::new(transport));
+        client
+    }
+
+    /// Send a `get` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn get<T>(&self, path: &str, url_params: T) -> StreakFuture<Value>
+        where T: serde::Serialize
+    {
+        self.send(Method::Get, "v1", path, url_params, None)
+    }
+
+    /// Send a `get` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn get_v2<T>(&self, path: &str, url_params: T) -> StreakFuture<Value>
+        where T: serde::Serialize
+    {
+        self.send(Method::Get, "v2", path, url_params, None)
+    }
+
+    /// Send a `post` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn post(&self, path: &str, body: RequestBody) -> StreakFuture<Value> {
+        self.send(Method::Post, "v1", path, (), Some(body))
+    }
+
+    /// Send a `post` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn post_v2(&self, path: &str, body: RequestBody) -> StreakFuture<Value> {
+        self.send(Method::Post, "v2", path, (), Some(body))
+    }
+
+    /// Send a `put` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn put(&self, path: &str, body: RequestBody) -> StreakFuture<Value> {
+        self.send(Method::Put, "v1", path, (), Some(body))
+    }
+
+    /// Send a `put` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn put_v2(&self, path: &str, body: RequestBody) -> StreakFuture<Value> {
+        self.send(Method::Put, "v2", path, (), Some(body))
+    }
+
+    /// Send a `delete` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn delete<T>(&self, path: &str, url_params: T) -> StreakFuture<Value>
+        where T: serde::Serialize
+    {
+        self.send(Method::Delete, "v1", path, url_params, None)
+    }
+
+    /// Send a `delete` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn delete_v2<T>(&self, path: &str, url_params: T) -> StreakFuture<Value>
+        where T: serde::Serialize
+    {
+        self.send(Method::Delete, "v2", path, url_params, None)
+    }
+
+    /// Send the request described by `endpoint` and deserialize the response.
+    pub fn execute<E>(&self, endpoint: &E) -> StreakFuture<E::Response>
+        where E: Endpoint,
+              E::Response: 'static
+    {
+        let url = endpoint.query().and_then(|query| {
+            client::endpoint_url(&self.api_url, endpoint.version().as_str(), &endpoint.path(), &query)
+        });
+        let request = url.and_then(|url| endpoint.body().map(|body| (url, body)));
+        match request {
+            Ok((url, body)) => Box::new(self.request(endpoint.method(), endpoint.is_idempotent(), url, body).and_then(|res| {
+                let response: E::Response = serde_json::from_value(res)?;
+                Ok(response)
+            })),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    fn send<T>(&self, method: Method, version: &str, path: &str, url_params: T, body: Option<RequestBody>) -> StreakFuture<Value>
+        where T: serde::Serialize
+    {
+        match self.url(version, path, url_params) {
+            Ok(url) => {
+                // Without an endpoint to ask, only reads are known to be safe
+                // to send twice.
+                let idempotent = method == Method::Get;
+                self.request(method, idempotent, url, body)
+            },
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    fn url<T>(&self, version: &str, path: &str, params: T) -> Result<Url, StreakError>
+        where T: serde::Serialize
+    {
+        client::build_url(&self.api_url, version, path, params)
+    }
+
+    fn request(&self, method: Method, idempotent: bool, url: Url, request_body: Option<RequestBody>) -> StreakFuture<Value> {
+        let reqwest = self.reqwest.clone();
+        let handle = self.handle.clone();
+        let headers = client::request_headers(&self.headers, &self.api_key, request_body.as_ref());
+        let retry_policy = client::retry_policy(&self.retry_policy, self.retry_count, self.retry_wait);
+        let path = client::redacted_path(&url, &self.api_key);
+        let transport = self.transport.clone();
+
+        Box::new(future::loop_fn(1, move |attempt| {
+            debug!("Attempting request - Method: {}. Url: {}", method, url);
+            if let Some(ref b) = request_body {
+                debug!("Request body - {}", b.as_str());
+            }
+
+            let response: StreakFuture<(StatusCode, Headers, String)> = match transport {
+                Some(ref transport) => {
+                    let request = HttpRequest {
+                        method: method.clone(),
+                        url: url.clone(),
+                        headers: headers.clone(),
+                        body: request_body.as_ref().map(|b| b.as_str().to_owned()),
+                    };
+                    Box::new(future::result(transport.send(request).map(|res| (res.status, res.headers, res.body))))
+                },
+                None => {
+                    let mut builder = reqwest.request(method.clone(), url.clone());
+                    builder.headers(headers.clone());
+                    if let Some(ref b) = request_body {
+                        builder.body(b.as_str().to_owned());
+                    }
+                    Box::new(builder.send().and_then(ReadBody::new).map_err(StreakError::from))
+                },
+            };
+
+            let response_method = method.clone();
+            let path = path.clone();
+            let handle = handle.clone();
+            let retry_policy = retry_policy.clone();
+            response
+                .and_then(move |(status, headers, body)| {
+                    debug!("Response body: {}", body);
+                    client::handle_response(&response_method, &path, status, &headers, &body)
+                })
+                .then(move |result| -> StreakFuture<Loop<Value, u32>> {
+                    let e = match result {
+                        Ok(value) => return Box::new(future::ok(Loop::Break(value))),
+                        Err(e) => e,
+                    };
+                    match retry_policy.retry_delay(idempotent, attempt, &e) {
+                        Some(delay) => {
+                            debug!("Retrying in {:?} after error: {}", delay, e);
+                            match Timeout::new(delay, &handle) {
+                                Ok(timeout) => Box::new(timeout.map(move |_| Loop::Continue(attempt + 1)).map_err(StreakError::from)),
+                                Err(e) => Box::new(future::err(StreakError::from(e))),
+                            }
+                        },
+                        None => Box::new(future::err(e)),
+                    }
+                })
+        }))
+    }
+}
+
+/// Reads the whole body of a response into memory.
+struct ReadBody {
+    response: Response,
+    body: Vec<u8>,
+}
+
+impl ReadBody {
+    fn new(response: Response) -> ReadBody {
+        ReadBody {
+            response: response,
+            body: Vec::new(),
+        }
+    }
+}
+
+impl Future for ReadBody {
+    type Item = (StatusCode, Headers, String);
+    type Error = reqwest::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            match self.response.body_mut().poll()? {
+                Async::Ready(Some(chunk)) => self.body.extend_from_slice(&chunk),
+                Async::Ready(None) => break,
+                Async::NotReady => return Ok(Async::NotReady),
+            }
+        }
+
+        let body = mem::replace(&mut self.body, Vec::new());
+        Ok(Async::Ready((self.response.status(),
+                         self.response.headers().clone(),
+                         String::from_utf8_lossy(&body).into_owned())))
+    }
+}
@@ -0,0 +1,33 @@
+//! Compare what the Streak API returns with the models of this library.
+//!
+//! Reads the API key from `STREAK_API_KEY`, or from a `.env` file. Prints a
+//! report for every request it makes, and exits with status 1 when any
+//! response differs from its model.
+
+extern crate dotenv;
+extern crate streak;
+
+use std::env;
+use std::process;
+
+use streak::Client;
+use streak::drift;
+
+fn main() {
+    dotenv::dotenv().ok();
+    let api_key = match env::var("STREAK_API_KEY") {
+        Ok(api_key) => api_key,
+        Err(_) => {
+            eprintln!("Set STREAK_API_KEY to the API key of the account to check");
+            process::exit(2);
+        },
+    };
+
+    let reports = drift::check(&Client::new(&api_key));
+    for report in &reports {
+        print!("{}", report);
+    }
+    if reports.iter().any(|r| !r.is_empty()) {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,207 @@
+//! Record and replay HTTP interactions with the Streak service.
+//!
+//! A `Cassette` is a `Transport` backed by a JSON fixture file. In record mode
+//! it forwards every request to another transport and writes the request and
+//! the response to the file. In replay mode it answers requests from the file
+//! without touching the network, so examples and tests recorded against a
+//! real Streak account can run anywhere.
+//!
+//! Request headers, and with them the API key, are never recorded.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::StatusCode;
+use reqwest::header::Headers;
+use serde_json::{self, Value};
+
+use error::StreakError;
+use transport::{HttpRequest, HttpResponse, Transport};
+
+/// A `Transport` that records interactions to, or replays them from, a file.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::Client;
+/// use streak::cassette::Cassette;
+///
+/// fn main() {
+///     let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/cassettes/pipelines_list.json");
+///     let cassette = Cassette::replay(path).expect("to load the cassette");
+///     let client = Client::with_transport("", cassette);
+///     let pipelines = streak::api::pipelines::list(&client).expect("list all pipelines");
+///     assert!(pipelines.len() > 0);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+#[derive(Debug)]
+enum Mode {
+    Record(Box<dyn Transport>),
+    Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+    #[serde(skip)]
+    used: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    /// The body, when it is JSON. Kept as a document so fixtures are readable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+    /// The body, when it is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+impl Cassette {
+    /// Send requests through `transport` and record them to the file at `path`,
+    /// replacing whatever it held before.
+    pub fn record<P, T>(path: P, transport: T) -> Cassette
+        where P: AsRef<Path>,
+              T: Transport + 'static
+    {
+        Cassette {
+            path: path.as_ref().to_owned(),
+            mode: Mode::Record(Box::new(transport)),
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answer requests with the interactions recorded in the file at `path`.
+    pub fn replay<P>(path: P) -> Result<Cassette, StreakError>
+        where P: AsRef<Path>
+    {
+        let mut contents = String::new();
+        File::open(path.as_ref())?.read_to_string(&mut contents)?;
+        let interactions = serde_json::from_str(&contents)?;
+
+        Ok(Cassette {
+            path: path.as_ref().to_owned(),
+            mode: Mode::Replay,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    fn save(&self, interactions: &[Interaction]) -> Result<(), StreakError> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(&self.path)?;
+        file.write_all(serde_json::to_string_pretty(interactions)?.as_bytes())?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl Transport for Cassette {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, StreakError> {
+        let recorded = RecordedRequest {
+            method: request.method.to_string(),
+            path: request_path(&request),
+            body: request.body.clone(),
+        };
+        let mut interactions = self.interactions.lock().expect("cassette lock");
+
+        match self.mode {
+            Mode::Record(ref transport) => {
+                let response = transport.send(request)?;
+                interactions.push(Interaction {
+                    request: recorded,
+                    response: record_response(&response),
+                    used: true,
+                });
+                self.save(&interactions)?;
+                Ok(response)
+            },
+            Mode::Replay => {
+                let interaction = interactions.iter_mut()
+                    .find(|i| !i.used && i.request.method == recorded.method && i.request.path == recorded.path);
+                match interaction {
+                    Some(interaction) => {
+                        interaction.used = true;
+                        replay_response(&interaction.response)
+                    },
+                    None => {
+                        let message = format!("{} has no recorded interaction left for {} {}",
+                                              self.path.display(), recorded.method, recorded.path);
+                        Err(io::Error::new(io::ErrorKind::NotFound, message).into())
+                    },
+                }
+            },
+        }
+    }
+}
+
+fn request_path(request: &HttpRequest) -> String {
+    match request.url.query() {
+        Some(query) if !query.is_empty() => format!("{}?{}", request.url.path(), query),
+        _ => request.url.path().to_owned(),
+    }
+}
+
+fn record_response(response: &HttpResponse) -> RecordedResponse {
+    let headers = response.headers.iter()
+        .filter(|h| !h.name().eq_ignore_ascii_case("set-cookie"))
+        .map(|h| (h.name().to_owned(), h.value_string()))
+        .collect();
+    let (json, text) = match serde_json::from_str(&response.body) {
+        Ok(value) => (Some(value), None),
+        Err(_) => (None, Some(response.body.clone())),
+    };
+
+    RecordedResponse {
+        status: response.status.as_u16(),
+        headers: headers,
+        json: json,
+        text: text,
+    }
+}
+
+fn replay_response(recorded: &RecordedResponse) -> Result<HttpResponse, StreakError> {
+    let mut headers = Headers::new();
+    for (name, value) in &recorded.headers {
+        headers.set_raw(name.clone(), value.clone());
+    }
+    let body = match (&recorded.json, &recorded.text) {
+        (&Some(ref json), _) => serde_json::to_string(json)?,
+        (&None, &Some(ref text)) => text.clone(),
+        (&None, &None) => String::new(),
+    };
+
+    let status = StatusCode::try_from(recorded.status).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("invalid recorded status {}", recorded.status))
+    })?;
+
+    Ok(HttpResponse {
+        status: status,
+        headers: headers,
+        body: body,
+    })
+}
+
@@ -1,17 +1,30 @@
-use std::thread;
 use std::io::Read;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 use reqwest::{self, StatusCode, Method, Url};
-use reqwest::header::{Headers, Authorization, Basic, ContentType, Location};
+use reqwest::header::{Headers, Header, Authorization, Basic, ContentType, Location, RetryAfter, UserAgent};
 use serde;
+use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 use serde_url_params;
 
 use std::env;
 use dotenv::dotenv;
 
-use error::StreakError;
+use cassette::Cassette;
+use endpoint::Endpoint;
+use error::{ApiError, StreakError};
+use retry::{ExponentialBackoff, RetryPolicy};
+use streaming::JsonArray;
+use transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport};
+
+#[cfg(feature = "async")]
+use tokio_core::reactor::Handle;
+#[cfg(feature = "async")]
+use async_client::AsyncClient;
 
 /// The Streak API Rust client.
 ///
@@ -19,16 +32,20 @@ use error::StreakError;
 ///
 #[derive(Debug)]
 pub struct Client {
-    /// Configure the client to retry the request `retry_count` number of times
-    /// when the service is unavailable.
+    /// Configure the client to send a request up to `retry_count` times in
+    /// total when it fails with a transient error, such as the service being
+    /// unavailable. Ignored when a `retry_policy` is set.
     pub retry_count: u8,
 
-    /// Duration of time to wait between retry attempts.
+    /// Milliseconds to wait before the first retry. Later retries wait
+    /// longer. Ignored when a `retry_policy` is set.
     pub retry_wait: u16,
 
     api_url: String,
     api_key: String,
-    reqwest: reqwest::Client,
+    headers: Headers,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    transport: Box<dyn Transport>,
 }
 
 /// Status message returned by every API request.
@@ -38,39 +55,111 @@ pub struct Status {
     pub error: String,
 }
 
+/// The body of a request that changes data on the Streak service.
+///
+/// Most endpoints take JSON, but some of the v1 endpoints only accept form
+/// encoded parameters.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RequestBody {
+    /// A JSON document, sent as `application/json`.
+    Json(String),
+
+    /// Form parameters, sent as `application/x-www-form-urlencoded`.
+    Form(String),
+}
+
+impl RequestBody {
+    /// Serialize `body` into a JSON request body.
+    pub fn json<T>(body: &T) -> Result<RequestBody, StreakError>
+        where T: serde::Serialize
+    {
+        Ok(RequestBody::Json(serde_json::to_string(body)?))
+    }
+
+    /// Serialize `body` into a form encoded request body.
+    pub fn form<T>(body: &T) -> Result<RequestBody, StreakError>
+        where T: serde::Serialize
+    {
+        Ok(RequestBody::Form(serde_url_params::to_string(body)?))
+    }
+
+    pub(crate) fn content_type(&self) -> ContentType {
+        match *self {
+            RequestBody::Json(_) => ContentType::json(),
+            RequestBody::Form(_) => ContentType::form_url_encoded(),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        match *self {
+            RequestBody::Json(ref s) => s,
+            RequestBody::Form(ref s) => s,
+        }
+    }
+}
+
 impl Client {
     /// Create a new client to the Streak service.
     ///
     /// Example:
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate streak;
     ///
     /// use streak::Client;
     /// use std::env;
     ///
     /// fn main() {
-    /// #   Client::example();
-    ///
     ///     let api_key = env::var("STREAK_API_KEY").expect("to have STREAK_API_KEY set");
     ///     let client = Client::new(&api_key);
     /// }
     /// ```
     pub fn new(api_key: &str) -> Client {
+        Client::with_transport(api_key, ReqwestTransport::new())
+    }
+
+    /// Create a new client that sends its requests through `transport`.
+    ///
+    /// This makes it possible to run the client against something other than
+    /// the live Streak service, such as a `transport::MockTransport`.
+    pub fn with_transport<T>(api_key: &str, transport: T) -> Client
+        where T: Transport + 'static
+    {
         Client {
-            retry_count: 3,
-            retry_wait: 250,
-            api_url: "https://www.streak.com/api".into(),
+            retry_count: RETRY_COUNT,
+            retry_wait: RETRY_WAIT,
+            api_url: API_URL.into(),
             api_key: api_key.into(),
-            reqwest: reqwest::Client::new(),
+            headers: default_headers(),
+            retry_policy: None,
+            transport: Box::new(transport),
         }
     }
 
+    /// Start configuring a client with a `ClientBuilder`.
+    pub fn builder(api_key: &str) -> ClientBuilder {
+        ClientBuilder::new(api_key)
+    }
+
+    /// A client for the examples in this documentation, backed by the named
+    /// cassette in `tests/fixtures/cassettes`. Each example has a cassette of
+    /// its own. The cassette is replayed, unless `STREAK_RECORD=1` is set in
+    /// the environment: then the example runs against the live service with
+    /// `STREAK_API_KEY` and records the cassette again.
     #[doc(hidden)]
-    pub fn example() -> Client {
-        dotenv().ok();
-        let api_key: String = env::var("STREAK_API_KEY").expect("to have STREAK_API_KEY set");
-        Client::new(&api_key)
+    pub fn example(cassette: &str) -> Client {
+        // Only the environment itself can turn recording on, not `.env`.
+        let record = env::var("STREAK_RECORD").map(|r| r == "1").unwrap_or(false);
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/cassettes")
+            .join(format!("{}.json", cassette));
+        if record {
+            dotenv().ok();
+            let api_key = env::var("STREAK_API_KEY").expect("to have STREAK_API_KEY set to record");
+            Client::with_transport(&api_key, Cassette::record(path, ReqwestTransport::new()))
+        } else {
+            Client::with_transport("", Cassette::replay(path).expect("to load the cassette"))
+        }
     }
 
     /// Send a `get` request to the Streak service. This is intended to be used
@@ -89,83 +178,552 @@ impl Client {
         self.request(Method::Get, self.url("v2", path, url_params)?, None)
     }
 
-    fn url<T>(&self, version: &str, path: &str, params: T) -> Result<Url, StreakError>
+    /// Send a `post` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn post(&self, path: &str, body: RequestBody) -> Result<Value, StreakError> {
+        self.request(Method::Post, self.url("v1", path, ())?, Some(body))
+    }
+
+    /// Send a `post` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn post_v2(&self, path: &str, body: RequestBody) -> Result<Value, StreakError> {
+        self.request(Method::Post, self.url("v2", path, ())?, Some(body))
+    }
+
+    /// Send a `put` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn put(&self, path: &str, body: RequestBody) -> Result<Value, StreakError> {
+        self.request(Method::Put, self.url("v1", path, ())?, Some(body))
+    }
+
+    /// Send a `put` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn put_v2(&self, path: &str, body: RequestBody) -> Result<Value, StreakError> {
+        self.request(Method::Put, self.url("v2", path, ())?, Some(body))
+    }
+
+    /// Send a `delete` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn delete<T>(&self, path: &str, url_params: T) -> Result<Value, StreakError>
         where T: serde::Serialize
     {
-        let mut base = format!("{api_url}/{version}/{path}",
-                           api_url = self.api_url,
-                           version = version,
-                           path = path);
+        self.request(Method::Delete, self.url("v1", path, url_params)?, None)
+    }
 
-        let encoded = serde_url_params::to_string(&params)?;
-        base = format!("{}?{}", base, encoded);
+    /// Send a `delete` request to the Streak service. This is intended to be used
+    /// by the library and not the user.
+    pub fn delete_v2<T>(&self, path: &str, url_params: T) -> Result<Value, StreakError>
+        where T: serde::Serialize
+    {
+        self.request(Method::Delete, self.url("v2", path, url_params)?, None)
+    }
 
-        Ok(Url::parse(&base)?)
+    /// Send the request described by `endpoint` and deserialize the response.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use streak::api::pipelines;
+    ///
+    /// fn main() {
+    ///     let client = streak::Client::example("client_execute");
+    ///     let pipelines = client.execute(&pipelines::List).expect("list all pipelines");
+    ///     assert!(pipelines.len() > 0);
+    /// }
+    /// ```
+    pub fn execute<E>(&self, endpoint: &E) -> Result<E::Response, StreakError>
+        where E: Endpoint
+    {
+        let url = endpoint_url(&self.api_url, endpoint.version().as_str(), &endpoint.path(), &endpoint.query()?)?;
+        let method = endpoint.method();
+        let path = redacted_path(&url, &self.api_key);
+        let res = self.send(&method, endpoint.is_idempotent(), url, endpoint.body()?)?;
+
+        match serde_json::from_str(&res.body) {
+            Ok(response) => Ok(response),
+            // Bodies that are not JSON at all, such as empty ones, are read
+            // the way `handle_response` reads them.
+            Err(ref e) if !e.is_data() => {
+                let value = handle_response(&method, &path, res.status, &res.headers, &res.body)?;
+                Ok(serde_json::from_value(value)?)
+            },
+            Err(e) => Err(e.into()),
+        }
     }
 
-    fn request(&self, method: Method, url: Url, request_body: Option<String>) -> Result<Value, StreakError> {
-        let mut count = self.retry_count;
-        loop {
-            let url = url.clone();
-
-            debug!("Attempting request - Method: {}. Url: {}", method, url);
-
-            let mut headers = Headers::new();
-            let credentials = Basic {
-                username: self.api_key.clone(),
-                password: Some("X".into()),
-            };
-            headers.set(Authorization(credentials));
-            headers.set(ContentType::json());
-            let mut res = match request_body.clone() {
-                Some(b) => {
-                    debug!("Request body - {}", b);
-                    self.reqwest.request(method.clone(), url).headers(headers).body(b).send()?
-                },
-                None => self.reqwest.request(method.clone(), url).headers(headers).send()?,
-            };
+    /// Send the request described by `endpoint`, whose response is a list,
+    /// and read the items of the list one at a time as they arrive, without
+    /// holding the whole response in memory.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use streak::api::boxes;
+    /// use streak::testing::FakeStreak;
+    ///
+    /// fn main() {
+    ///     let streak = FakeStreak::start().expect("to start the fake service");
+    ///     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+    ///     streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+    ///     streak.add_box(&pipeline.pipeline_key, "Globex", &pipeline.stage_order[0]);
+    ///     let client = streak.client();
+    ///
+    ///     let names = client.execute_iter(&boxes::List::new(&pipeline.pipeline_key))
+    ///         .expect("list boxes")
+    ///         .map(|b| b.map(|b| b.name))
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .expect("read every box");
+    ///     assert_eq!(names, vec!["ACME", "Globex"]);
+    /// }
+    /// ```
+    pub fn execute_iter<E, T>(&self, endpoint: &E) -> Result<JsonArray<T>, StreakError>
+        where E: Endpoint<Response = Vec<T>>,
+              T: DeserializeOwned
+    {
+        let url = endpoint_url(&self.api_url, endpoint.version().as_str(), &endpoint.path(), &endpoint.query()?)?;
+        let method = endpoint.method();
+        let path = redacted_path(&url, &self.api_key);
+        let request_body = endpoint.body()?;
 
+        let res = self.retrying(endpoint.is_idempotent(), || {
+            let mut res = self.transport.send_streaming(self.http_request(&method, &url, request_body.as_ref()))?;
+            if res.status.is_success() {
+                return Ok(res);
+            }
             let mut body = String::new();
-            res.read_to_string(&mut body)?;
-
+            res.body.read_to_string(&mut body)?;
             debug!("Response body: {}", body);
+            Err(status_error(&method, &path, res.status, &res.headers, &body))
+        })?;
+        Ok(JsonArray::new(res.body))
+    }
 
-            match serde_json::from_str::<Value>(&body) {
-                Ok(value) => {
-                    let status = serde_json::from_value(value.clone());
-
-                    match res.status() {
-                        StatusCode::Ok => return Ok(value),
-                        StatusCode::Created => return Ok(value),
-                        StatusCode::BadRequest => return Err(StreakError::BadRequest(status?)),
-                        StatusCode::Unauthorized => return Err(StreakError::UnauthorizedKey(status?)),
-                        StatusCode::Forbidden => return Err(StreakError::Forbidden(status?)),
-                        StatusCode::NotFound => return Err(StreakError::UserNotFound(status?)),
-                        StatusCode::InternalServerError => return Err(StreakError::InternalServerError(status?)),
-                        s => panic!("Status code not covered in Streak REST specification: {}", s),
-                    };
-                },
-                Err(_) => {
-                    debug!("response headers: {}",res.headers());
-                    if res.headers().has::<Location>() {
-                        return Ok(serde_json::Value::String("Created".into()));
-                    } else { 
-                            match res.status() {
-                            StatusCode::ServiceUnavailable => {
-                                count -= 1;
-                                if count == 0 {
-                                    return Err(StreakError::ServiceUnavailable);
-                                }
-                                    else {
-                                        thread::sleep(Duration::from_millis(self.retry_wait.into()));
-                                        continue;
-                                    }
-                            },
-                            _ => return Err(StreakError::InvalidServerResponse),
-                        }
-                    }
+    fn url<T>(&self, version: &str, path: &str, params: T) -> Result<Url, StreakError>
+        where T: serde::Serialize
+    {
+        build_url(&self.api_url, version, path, params)
+    }
+
+    fn request(&self, method: Method, url: Url, request_body: Option<RequestBody>) -> Result<Value, StreakError> {
+        let path = redacted_path(&url, &self.api_key);
+        // Without an endpoint to ask, only reads are known to be safe to send
+        // twice.
+        let idempotent = method == Method::Get;
+        let res = self.send(&method, idempotent, url, request_body)?;
+        handle_response(&method, &path, res.status, &res.headers, &res.body)
+    }
+
+    /// Send a request until it succeeds or the retry policy gives up, and
+    /// return the successful response.
+    fn send(&self, method: &Method, idempotent: bool, url: Url, request_body: Option<RequestBody>) -> Result<HttpResponse, StreakError> {
+        let path = redacted_path(&url, &self.api_key);
+        self.retrying(idempotent, || {
+            let res = self.transport.send(self.http_request(method, &url, request_body.as_ref()))?;
+            debug!("Response body: {}", res.body);
+            if res.status.is_success() {
+                Ok(res)
+            } else {
+                Err(status_error(method, &path, res.status, &res.headers, &res.body))
+            }
+        })
+    }
+
+    fn http_request(&self, method: &Method, url: &Url, request_body: Option<&RequestBody>) -> HttpRequest {
+        debug!("Attempting request - Method: {}. Url: {}", method, url);
+        if let Some(b) = request_body {
+            debug!("Request body - {}", b.as_str());
+        }
+
+        HttpRequest {
+            method: method.clone(),
+            url: url.clone(),
+            headers: request_headers(&self.headers, &self.api_key, request_body),
+            body: request_body.map(|b| b.as_str().to_owned()),
+        }
+    }
+
+    fn retrying<T, F>(&self, idempotent: bool, mut attempt: F) -> Result<T, StreakError>
+        where F: FnMut() -> Result<T, StreakError>
+    {
+        let retry_policy = retry_policy(&self.retry_policy, self.retry_count, self.retry_wait);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match attempt() {
+                Ok(value) => return Ok(value),
+                Err(e) => match retry_policy.retry_delay(idempotent, attempts, &e) {
+                    Some(delay) => {
+                        debug!("Retrying in {:?} after error: {}", delay, e);
+                        thread::sleep(delay);
+                    },
+                    None => return Err(e),
                 },
-            };
+            }
+        }
+    }
+}
+
+/// Configure and create a `Client`.
+///
+/// Example:
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use std::time::Duration;
+/// use streak::Client;
+///
+/// fn main() {
+///     let client = Client::builder("api-key")
+///         .api_url("http://localhost:8080/api")
+///         .timeout(Duration::from_secs(10))
+///         .user_agent("crm-sync/1.0")
+///         .retry_count(5)
+///         .retry_wait(500)
+///         .build()
+///         .expect("to build a client");
+///     assert_eq!(client.retry_count, 5);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    api_key: String,
+    api_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    proxy: Option<String>,
+    headers: Headers,
+    retry_count: u8,
+    retry_wait: u16,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    transport: Option<Box<dyn Transport>>,
+}
+
+impl ClientBuilder {
+    /// Start with the same settings `Client::new` uses.
+    pub fn new(api_key: &str) -> ClientBuilder {
+        ClientBuilder {
+            api_key: api_key.into(),
+            api_url: API_URL.into(),
+            timeout: None,
+            connect_timeout: None,
+            read_timeout: None,
+            proxy: None,
+            headers: default_headers(),
+            retry_count: RETRY_COUNT,
+            retry_wait: RETRY_WAIT,
+            retry_policy: None,
+            transport: None,
         }
+    }
+
+    /// The root of the Streak API, `https://www.streak.com/api` by default.
+    /// Versioned paths such as `v1/pipelines` are appended to it.
+    pub fn api_url(mut self, api_url: &str) -> ClientBuilder {
+        self.api_url = api_url.trim_end_matches('/').into();
+        self
+    }
+
+    /// Give up on a request when it takes longer than `timeout` from start to
+    /// finish: connecting, sending the request and reading the response all
+    /// count against it.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Give up on a request when connecting to the service takes longer than
+    /// `connect_timeout`.
+    ///
+    /// `reqwest` 0.8 has a single deadline for the whole request and can not
+    /// time out connecting and reading separately. A client with both a
+    /// connect and a read timeout therefore gives up once their sum has
+    /// passed, and `build` fails with `UnsupportedSetting` when only one of
+    /// the two is set.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use std::time::Duration;
+    /// use streak::{Client, StreakError};
+    ///
+    /// fn main() {
+    ///     Client::builder("api-key")
+    ///         .connect_timeout(Duration::from_secs(5))
+    ///         .read_timeout(Duration::from_secs(30))
+    ///         .build()
+    ///         .expect("to build a client");
+    ///
+    ///     match Client::builder("api-key").connect_timeout(Duration::from_secs(5)).build() {
+    ///         Err(StreakError::UnsupportedSetting(_)) => {},
+    ///         other => panic!("expected the connect timeout to be refused, got {:?}", other),
+    ///     }
+    /// }
+    /// ```
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Give up on a request when sending it and reading the response takes
+    /// longer than `read_timeout` once connected. See `connect_timeout` for
+    /// how it is enforced.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> ClientBuilder {
+        self.read_timeout = Some(read_timeout);
+        self
+    }
+
+    /// Send every request through the proxy at `proxy_url`.
+    pub fn proxy(mut self, proxy_url: &str) -> ClientBuilder {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Send `header` with every request.
+    pub fn default_header<H>(mut self, header: H) -> ClientBuilder
+        where H: Header
+    {
+        self.headers.set(header);
+        self
+    }
+
+    /// Identify the application in the `User-Agent` header of every request.
+    pub fn user_agent(self, user_agent: &str) -> ClientBuilder {
+        self.default_header(UserAgent::new(user_agent.to_owned()))
+    }
+
+    /// See `Client::retry_count`.
+    pub fn retry_count(mut self, retry_count: u8) -> ClientBuilder {
+        self.retry_count = retry_count;
+        self
+    }
+
+    /// See `Client::retry_wait`.
+    pub fn retry_wait(mut self, retry_wait: u16) -> ClientBuilder {
+        self.retry_wait = retry_wait;
+        self
+    }
+
+    /// Decide which failed requests are retried with `retry_policy` instead of
+    /// the default `ExponentialBackoff`.
+    pub fn retry_policy<P>(mut self, retry_policy: P) -> ClientBuilder
+        where P: RetryPolicy + 'static
+    {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Send requests through `transport` instead of `reqwest`. The timeout and
+    /// proxy settings are left to the transport when one is given. An `AsyncClient`
+    /// blocks its reactor while the transport sends a request.
+    pub fn transport<T>(mut self, transport: T) -> ClientBuilder
+        where T: Transport + 'static
+    {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Create the configured `Client`.
+    pub fn build(self) -> Result<Client, StreakError> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.deadline()? {
+                    builder.timeout(timeout);
+                }
+                if let Some(ref proxy) = self.proxy {
+                    builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+                }
+                Box::new(ReqwestTransport::with_client(builder.build()?))
+            },
+        };
+
+        Ok(Client {
+            retry_count: self.retry_count,
+            retry_wait: self.retry_wait,
+            api_url: self.api_url,
+            api_key: self.api_key,
+            headers: self.headers,
+            retry_policy: self.retry_policy,
+            transport: transport,
+        })
+    }
+
+    /// Create the configured `AsyncClient`, running on the reactor behind
+    /// `handle`. The asynchronous `reqwest` client it uses has no timeout or
+    /// proxy settings, so setting any of them fails with `UnsupportedSetting`
+    /// unless a transport is given.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    /// extern crate tokio_core;
+    ///
+    /// use std::time::Duration;
+    /// use streak::{Client, StreakError};
+    /// use tokio_core::reactor::Core;
+    ///
+    /// fn main() {
+    ///     let core = Core::new().expect("to create a reactor");
+    ///     match Client::builder("api-key").timeout(Duration::from_secs(10)).build_async(&core.handle()) {
+    ///         Err(StreakError::UnsupportedSetting(_)) => {},
+    ///         other => panic!("expected the timeout to be refused, got {:?}", other),
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn build_async(self, handle: &Handle) -> Result<AsyncClient, StreakError> {
+        let mut client = match self.transport {
+            Some(transport) => {
+                let mut client = AsyncClient::new(&self.api_key, handle);
+                client.transport = Some(Arc::from(transport));
+                client
+            },
+            None => {
+                if self.timeout.is_some() || self.connect_timeout.is_some() || self.read_timeout.is_some() {
+                    return Err(StreakError::UnsupportedSetting("an AsyncClient can not time out requests".into()));
+                }
+                if self.proxy.is_some() {
+                    return Err(StreakError::UnsupportedSetting("an AsyncClient can not use a proxy".into()));
+                }
+                AsyncClient::new(&self.api_key, handle)
+            },
+        };
+        client.retry_count = self.retry_count;
+        client.retry_wait = self.retry_wait;
+        client.retry_policy = self.retry_policy;
+        client.api_url = self.api_url;
+        client.headers = self.headers;
+        Ok(client)
+    }
+
+    /// The one deadline `reqwest` 0.8 can enforce for the configured timeouts.
+    fn deadline(&self) -> Result<Option<Duration>, StreakError> {
+        let split = match (self.connect_timeout, self.read_timeout) {
+            (Some(connect), Some(read)) => connect.checked_add(read),
+            (None, None) => None,
+            _ => return Err(StreakError::UnsupportedSetting(
+                "reqwest can not time out connecting and reading separately, set both a connect and a read timeout".into())),
+        };
+        Ok(match (self.timeout, split) {
+            (Some(timeout), Some(split)) => Some(if timeout < split { timeout } else { split }),
+            (timeout, None) => timeout,
+            (None, split) => split,
+        })
+    }
+}
+
+pub(crate) const API_URL: &'static str = "https://www.streak.com/api";
+
+pub(crate) const RETRY_COUNT: u8 = 3;
+
+pub(crate) const RETRY_WAIT: u16 = 250;
+
+/// The policy a client retries with: the one it was given, or else an
+/// `ExponentialBackoff` following its `retry_count` and `retry_wait`.
+pub(crate) fn retry_policy(policy: &Option<Arc<dyn RetryPolicy>>, retry_count: u8, retry_wait: u16) -> Arc<dyn RetryPolicy> {
+    match *policy {
+        Some(ref policy) => policy.clone(),
+        None => Arc::new(ExponentialBackoff {
+            max_attempts: retry_count.into(),
+            initial_delay: Duration::from_millis(retry_wait.into()),
+            .. ExponentialBackoff::default()
+        }),
+    }
 }
+
+pub(crate) fn build_url<T>(api_url: &str, version: &str, path: &str, params: T) -> Result<Url, StreakError>
+    where T: serde::Serialize
+{
+    let encoded = serde_url_params::to_string(&params)?;
+    endpoint_url(api_url, version, path, &encoded)
+}
+
+pub(crate) fn endpoint_url(api_url: &str, version: &str, path: &str, query: &str) -> Result<Url, StreakError> {
+    let base = format!("{api_url}/{version}/{path}?{query}",
+                       api_url = api_url,
+                       version = version,
+                       path = path,
+                       query = query);
+
+    Ok(Url::parse(&base)?)
+}
+
+pub(crate) fn default_headers() -> Headers {
+    let mut headers = Headers::new();
+    headers.set(UserAgent::new(concat!("streak-rs/", env!("CARGO_PKG_VERSION"))));
+    headers
+}
+
+pub(crate) fn request_headers(defaults: &Headers, api_key: &str, body: Option<&RequestBody>) -> Headers {
+    let mut headers = defaults.clone();
+    let credentials = Basic {
+        username: api_key.into(),
+        password: Some("X".into()),
+    };
+    headers.set(Authorization(credentials));
+    headers.set(body.map_or_else(ContentType::json, RequestBody::content_type));
+    headers
+}
+
+/// The path and query of `url`, with `api_key` redacted, for error messages.
+pub(crate) fn redacted_path(url: &Url, api_key: &str) -> String {
+    let path = match url.query() {
+        Some(query) if !query.is_empty() => format!("{}?{}", url.path(), query),
+        _ => url.path().to_owned(),
+    };
+    if api_key.is_empty() {
+        path
+    } else {
+        path.replace(api_key, "[REDACTED]")
+    }
+}
+
+pub(crate) fn handle_response(method: &Method, path: &str, status: StatusCode, headers: &Headers, body: &str) -> Result<Value, StreakError> {
+    if !status.is_success() {
+        return Err(status_error(method, path, status, headers, body));
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => Ok(value),
+        Err(_) if headers.has::<Location>() => Ok(serde_json::Value::String("Created".into())),
+        Err(_) if body.trim().is_empty() => Ok(Value::Null),
+        Err(_) => Err(StreakError::InvalidServerResponse(ApiError::new(method.clone(), path, status.as_u16(), body))),
+    }
+}
+
+/// The error for a response with a status other than success.
+pub(crate) fn status_error(method: &Method, path: &str, status: StatusCode, headers: &Headers, body: &str) -> StreakError {
+    let error = ApiError::new(method.clone(), path, status.as_u16(), body);
+    debug!("response headers: {}", headers);
+
+    match status {
+        StatusCode::BadRequest => StreakError::BadRequest(error),
+        StatusCode::Unauthorized => StreakError::UnauthorizedKey(error),
+        StatusCode::Forbidden => StreakError::Forbidden(error),
+        StatusCode::NotFound => StreakError::NotFound {
+            resource: error.resource().into(),
+            error: error,
+        },
+        StatusCode::Conflict => StreakError::Conflict(error),
+        StatusCode::TooManyRequests => StreakError::RateLimited {
+            retry_after: retry_after(headers),
+            error: error,
+        },
+        StatusCode::InternalServerError => StreakError::InternalServerError(error),
+        StatusCode::BadGateway => StreakError::BadGateway(error),
+        StatusCode::ServiceUnavailable => StreakError::ServiceUnavailable(error),
+        StatusCode::GatewayTimeout => StreakError::GatewayTimeout(error),
+        _ => StreakError::HttpStatus(error),
+    }
+}
+
+/// The delay asked for by a `Retry-After` header, if there is one.
+fn retry_after(headers: &Headers) -> Option<Duration> {
+    match headers.get::<RetryAfter>() {
+        Some(&RetryAfter::Delay(delay)) => Some(delay),
+        Some(&RetryAfter::DateTime(date)) => SystemTime::from(date).duration_since(SystemTime::now()).ok(),
+        None => None,
+    }
 }
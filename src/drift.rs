@@ -0,0 +1,343 @@
+//! Finding where the Streak API and the models of this library disagree.
+//!
+//! `check` calls each supported endpoint and reads its raw JSON next to the
+//! model it is deserialized into. It reports the properties Streak returns
+//! that the models do not know, the properties the models expect that are
+//! missing, and values of the wrong kind, such as a float in a date field.
+//! A request that fails is reported as such, and the others are still made.
+//! The `streak-drift` binary runs it against the account of
+//! `STREAK_API_KEY` and prints what it finds.
+//!
+//! ```rust
+//! extern crate serde_json;
+//! extern crate streak;
+//!
+//! use std::collections::HashMap;
+//!
+//! use serde_json::Value;
+//! use streak::api::boxes::{ContactHandle, FieldValue};
+//! use streak::api::pipelines::FieldType;
+//! use streak::drift::{self, Drift};
+//! use streak::testing::FakeStreak;
+//!
+//! fn main() {
+//!     let streak = FakeStreak::start().expect("to start the fake service");
+//!     let pipeline = streak.add_pipeline("Sales", &["Lead"]);
+//!     let close_date = streak.add_field(&pipeline.pipeline_key, "Close date", FieldType::Date);
+//!     let mut acme = streak.add_box(&pipeline.pipeline_key, "ACME", &pipeline.stage_order[0]);
+//!     acme.extra.insert("archived".into(), Value::Bool(false));
+//!     acme.fields.insert(close_date.key.clone(), FieldValue::Float(1.5));
+//!     acme.contacts = Some(vec![ContactHandle { is_starred: false, key: "gone".into(), extra: HashMap::new() }]);
+//!     streak.insert_box(acme);
+//!
+//!     let reports = drift::check(&streak.client());
+//!     let boxes = reports.iter()
+//!         .find(|r| r.request == format!("GET v1/pipelines/{}/boxes", pipeline.pipeline_key))
+//!         .expect("a report on the boxes");
+//!     assert_eq!(boxes.drift, vec![
+//!         Drift::Unknown("0.archived".into()),
+//!         Drift::Mismatch {
+//!             path: format!("0.fields.{}", close_date.key),
+//!             expected: "DATE".into(),
+//!             found: "float".into(),
+//!         },
+//!     ]);
+//!     assert_eq!(reports[0].request, "GET v1/pipelines");
+//!     assert!(reports[0].is_empty());
+//!
+//!     let contact = reports.iter().position(|r| r.request == "GET v2/contacts/gone").expect("a report on the contact");
+//!     assert_eq!(reports[contact].drift.len(), 1);
+//!     match reports[contact].drift[0] {
+//!         Drift::Failed(_) => {},
+//!         ref other => panic!("expected the request to fail, got {:?}", other),
+//!     }
+//!     assert_eq!(reports[contact + 1].request, "GET v1/search");
+//! }
+//! ```
+
+use std::fmt;
+
+use reqwest::Method;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+
+use api::{boxes, contacts, pipelines, search, Model};
+use api::boxes::FieldValue;
+use api::pipelines::{fields, stages, Pipeline};
+use client::{Client, RequestBody};
+use endpoint::{ApiVersion, Endpoint};
+use error::StreakError;
+
+/// How many Boxes of each Pipeline `check` reads.
+const SAMPLE_SIZE: u32 = 20;
+
+/// A way a response differs from its model. Paths name properties the way
+/// the JSON does, with list positions and map keys in between, such as
+/// `0.stages.5001.name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// The response has a property the model does not know.
+    Unknown(String),
+    /// The model expects a property the response does not have.
+    Missing(String),
+    /// A property holds a different kind of value than the model expects.
+    Mismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    /// The response could not be read into its model at all.
+    Invalid(String),
+    /// The request failed, so there was no response to compare.
+    Failed(String),
+}
+
+/// What `check` found in the response to one request.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The request, such as `GET v1/pipelines`.
+    pub request: String,
+    pub drift: Vec<Drift>,
+}
+
+impl Report {
+    /// Whether the response matched its model.
+    pub fn is_empty(&self) -> bool {
+        self.drift.is_empty()
+    }
+}
+
+/// Compare the JSON of a response with the model `T` it is read into.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate serde_json;
+/// extern crate streak;
+///
+/// use streak::api::pipelines::Stage;
+/// use streak::drift::{self, Drift};
+///
+/// fn main() {
+///     let raw = json!({
+///         "name": "Lead",
+///         "key": "5001",
+///         "color": {"foregroundColor": "#ffffff", "backgroundColor": "#3d85c6", "opacity": 1},
+///         "archived": false
+///     });
+///     assert_eq!(drift::compare::<Stage>(&raw), vec![
+///         Drift::Unknown("archived".into()),
+///         Drift::Missing("boxCount".into()),
+///         Drift::Unknown("color.opacity".into()),
+///     ]);
+/// }
+/// ```
+pub fn compare<T>(raw: &Value) -> Vec<Drift>
+    where T: DeserializeOwned + Serialize + Model
+{
+    read::<T>(raw).1
+}
+
+/// Call each supported endpoint and compare its response with its model.
+///
+/// Lists the Pipelines with their Stages and Fields, a sample of the Boxes
+/// of each Pipeline, and for the first Box of each its comments, its
+/// Contacts and a search for its name. A request that fails is reported
+/// with `Drift::Failed`, and what depends on its response is left out.
+pub fn check(client: &Client) -> Vec<Report> {
+    let mut reports = Vec::new();
+
+    let pipelines = run(client, &pipelines::List, &mut reports);
+    for pipeline in pipelines.unwrap_or_default() {
+        let key = &pipeline.pipeline_key;
+        run(client, &stages::List::new(key), &mut reports);
+        run(client, &fields::List::new(key), &mut reports);
+
+        let sample = run(client, &boxes::List::new(key).page(0, SAMPLE_SIZE), &mut reports).unwrap_or_default();
+        if let Some(report) = reports.last_mut() {
+            for (i, b) in sample.iter().enumerate() {
+                report.drift.extend(field_drift(&i.to_string(), b, &pipeline));
+            }
+        }
+
+        if let Some(b) = sample.first() {
+            run(client, &boxes::Get::new(&b.box_key), &mut reports);
+            run(client, &boxes::comments::List::new(&b.box_key), &mut reports);
+            for contact in b.contacts.iter().flat_map(|c| c.iter()) {
+                run(client, &contacts::Get::new(&contact.key), &mut reports);
+            }
+            run(client, &search::query(&b.name), &mut reports);
+        }
+    }
+    reports
+}
+
+/// Send `endpoint`, report on its response and return the model read from
+/// it, if it could be read.
+fn run<E>(client: &Client, endpoint: &E, reports: &mut Vec<Report>) -> Option<E::Response>
+    where E: Endpoint,
+          E::Response: Serialize + Model
+{
+    let (model, drift) = match client.execute(&Raw(endpoint)) {
+        Ok(raw) => read::<E::Response>(&raw),
+        Err(e) => (None, vec![Drift::Failed(e.to_string())]),
+    };
+    reports.push(Report {
+        request: format!("{} {}/{}", endpoint.method(), endpoint.version(), endpoint.path()),
+        drift: drift,
+    });
+    model
+}
+
+fn read<T>(raw: &Value) -> (Option<T>, Vec<Drift>)
+    where T: DeserializeOwned + Serialize + Model
+{
+    let model: T = match serde_json::from_value(raw.clone()) {
+        Ok(model) => model,
+        Err(e) => return (None, vec![Drift::Invalid(e.to_string())]),
+    };
+
+    let mut drift: Vec<_> = model.unknown_fields().into_iter().map(Drift::Unknown).collect();
+    match serde_json::to_value(&model) {
+        Ok(known) => walk("", raw, &known, &mut drift),
+        Err(e) => drift.push(Drift::Invalid(e.to_string())),
+    }
+    (Some(model), drift)
+}
+
+/// Compare `raw` with `known`, the model read from it written back as JSON.
+///
+/// Unknown properties kept in `extra` are written back as they were, and
+/// are already reported by `Model::unknown_fields`. Those of parts without
+/// an `extra` map, such as `Color`, are dropped and show up here.
+fn walk(path: &str, raw: &Value, known: &Value, drift: &mut Vec<Drift>) {
+    match (raw, known) {
+        (&Value::Object(ref raw), &Value::Object(ref known)) => {
+            let mut names: Vec<&String> = known.keys().chain(raw.keys().filter(|n| !known.contains_key(*n))).collect();
+            names.sort();
+            for name in names {
+                let path = join(path, name);
+                match (raw.get(name), known.get(name)) {
+                    (Some(raw), Some(known)) => walk(&path, raw, known, drift),
+                    (None, Some(known)) if !known.is_null() => drift.push(Drift::Missing(path)),
+                    (Some(raw), None) if !raw.is_null() => drift.push(Drift::Unknown(path)),
+                    _ => {},
+                }
+            }
+        },
+        (&Value::Array(ref raw), &Value::Array(ref known)) => {
+            for (i, (raw, known)) in raw.iter().zip(known).enumerate() {
+                walk(&join(path, &i.to_string()), raw, known, drift);
+            }
+        },
+        _ if kind(raw, false) != kind(known, false) => drift.push(Drift::Mismatch {
+            path: path.into(),
+            expected: kind(known, false).into(),
+            found: kind(raw, false).into(),
+        }),
+        _ => {},
+    }
+}
+
+/// The field values of `b` that do not suit the type of their field.
+fn field_drift(path: &str, b: &boxes::Box, pipeline: &Pipeline) -> Vec<Drift> {
+    let mut keys: Vec<&String> = b.fields.keys().collect();
+    keys.sort();
+    keys.into_iter()
+        .filter_map(|key| {
+            let field = pipeline.field(key)?;
+            let value = &b.fields[key];
+            value.typed(field).err().map(|_| Drift::Mismatch {
+                path: join(path, &format!("fields.{}", key)),
+                expected: field.field_type.to_string(),
+                found: value_kind(value).into(),
+            })
+        })
+        .collect()
+}
+
+fn value_kind(value: &FieldValue) -> &'static str {
+    serde_json::to_value(value).map(|v| kind(&v, true)).unwrap_or("value")
+}
+
+/// The name of the kind of a JSON value. Numbers are told apart as integers
+/// and floats only when `numbers` is set, as a float model written back as
+/// JSON may look like an integer.
+fn kind(value: &Value, numbers: bool) -> &'static str {
+    match *value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(ref n) if numbers && n.is_f64() => "float",
+        Value::Number(_) if numbers => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.into()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// An endpoint answering with the raw JSON of another one.
+struct Raw<'a, E: 'a>(&'a E);
+
+impl<'a, E: Endpoint> Endpoint for Raw<'a, E> {
+    type Response = Value;
+
+    fn method(&self) -> Method {
+        self.0.method()
+    }
+
+    fn version(&self) -> ApiVersion {
+        self.0.version()
+    }
+
+    fn path(&self) -> String {
+        self.0.path()
+    }
+
+    fn query(&self) -> Result<String, StreakError> {
+        self.0.query()
+    }
+
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        self.0.body()
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.0.is_idempotent()
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Drift::Unknown(ref path) => write!(f, "unknown property {}", path),
+            Drift::Missing(ref path) => write!(f, "missing property {}", path),
+            Drift::Mismatch { ref path, ref expected, ref found } => {
+                write!(f, "{} holds a {} where a {} is expected", path, found, expected)
+            },
+            Drift::Invalid(ref e) => write!(f, "unreadable response: {}", e),
+            Drift::Failed(ref e) => write!(f, "request failed: {}", e),
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.drift.is_empty() {
+            return writeln!(f, "{}: matches the models", self.request);
+        }
+        writeln!(f, "{}:", self.request)?;
+        for drift in &self.drift {
+            writeln!(f, "  {}", drift)?;
+        }
+        Ok(())
+    }
+}
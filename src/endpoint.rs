@@ -0,0 +1,129 @@
+//! Typed descriptions of the requests the Streak API understands.
+
+use std::fmt;
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+
+use client::RequestBody;
+use error::StreakError;
+
+/// The version of the Streak API an endpoint belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// The version as it appears in request paths, such as `v1`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request to the Streak API and the type of its response.
+///
+/// An endpoint only describes the request. Send it with `Client::execute`,
+/// or `AsyncClient::execute` with the `async` feature, and the response is
+/// deserialized into `Response`. Only `path` is required: endpoints default
+/// to a `GET` against the v1 API, without query parameters or a body.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::Client;
+/// use streak::endpoint::{ApiVersion, Endpoint};
+/// use streak::transport::{Method, MockTransport, StatusCode};
+///
+/// #[derive(Debug)]
+/// struct StageNames {
+///     pipeline_key: String,
+/// }
+///
+/// impl Endpoint for StageNames {
+///     type Response = Vec<String>;
+///
+///     fn version(&self) -> ApiVersion {
+///         ApiVersion::V2
+///     }
+///
+///     fn path(&self) -> String {
+///         format!("pipelines/{}/stages/names", self.pipeline_key)
+///     }
+/// }
+///
+/// fn main() {
+///     let mock = MockTransport::new();
+///     mock.respond(Method::Get, "/v2/pipelines/p/stages/names", StatusCode::Ok, r#"["Lead", "Won"]"#);
+///     let client = Client::with_transport("api-key", mock);
+///
+///     let names = client.execute(&StageNames { pipeline_key: "p".into() }).expect("stage names");
+///     assert_eq!(names, vec!["Lead", "Won"]);
+/// }
+/// ```
+pub trait Endpoint {
+    /// What the service answers with.
+    type Response: DeserializeOwned;
+
+    /// The HTTP method of the request.
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    /// The version of the API the endpoint belongs to.
+    fn version(&self) -> ApiVersion {
+        ApiVersion::V1
+    }
+
+    /// The path of the endpoint below the API version, such as `pipelines`.
+    fn path(&self) -> String;
+
+    /// The url encoded query string, without the leading `?`.
+    fn query(&self) -> Result<String, StreakError> {
+        Ok(String::new())
+    }
+
+    /// The body of the request, if it has one.
+    fn body(&self) -> Result<Option<RequestBody>, StreakError> {
+        Ok(None)
+    }
+
+    /// Whether sending the request twice has the same effect as sending it
+    /// once, so that it can be retried after a failure it may have gone
+    /// through despite. Streak creates resources with `PUT`, so only `GET`
+    /// requests are by default. Deletes are not retried either: when a delete
+    /// goes through but its answer is lost, the retry would report the
+    /// deleted resource as `NotFound`.
+    ///
+    /// ```rust
+    /// extern crate streak;
+    ///
+    /// use streak::Client;
+    /// use streak::api::boxes;
+    /// use streak::transport::{Method, MockTransport, StatusCode};
+    ///
+    /// fn main() {
+    ///     let mock = MockTransport::new();
+    ///     mock.respond(Method::Put, "/v1/pipelines/p/boxes", StatusCode::ServiceUnavailable, "");
+    ///     mock.respond(Method::Delete, "/v1/boxes/b", StatusCode::ServiceUnavailable, "");
+    ///     let client = Client::with_transport("api-key", mock.clone());
+    ///
+    ///     assert!(boxes::create(&client, &"p".into(), "ACME", &"5001".into()).is_err());
+    ///     assert!(boxes::delete(&client, &"b".into()).is_err());
+    ///     assert_eq!(mock.requests().len(), 2);
+    /// }
+    /// ```
+    fn is_idempotent(&self) -> bool {
+        self.method() == Method::Get
+    }
+}
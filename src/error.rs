@@ -1,51 +1,278 @@
 use std::error;
 use std::io;
 use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
 
-use reqwest;
+use reqwest::{self, Method};
 use serde_json;
 use serde_url_params;
 
 use client::Status;
 
 /// The error type used by this library.
-#[derive(PartialEq, Eq, Clone, Debug)]
+///
+/// Every HTTP status the service can answer with maps to a variant, whether
+/// or not the response body is JSON. Those variants carry an `ApiError` that
+/// records which request failed and what the service said about it. Errors
+/// raised by `reqwest`, `serde_json` and IO are available through `source()`.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::{Client, StreakError};
+/// use streak::transport::{Method, MockTransport, StatusCode};
+///
+/// fn main() {
+///     let mock = MockTransport::new();
+///     mock.respond(Method::Get, "/v1/pipelines/a", StatusCode::Conflict, "<html>Conflict</html>");
+///     mock.respond(Method::Get, "/v1/pipelines/b", StatusCode::ImATeapot, "short and stout");
+///     let client = Client::with_transport("api-key", mock);
+///
+///     match streak::api::pipelines::get(&client, &"a".into()) {
+///         Err(StreakError::Conflict(e)) => {
+///             assert_eq!(e.method, Method::Get);
+///             assert_eq!(e.path, "/api/v1/pipelines/a");
+///             assert_eq!(e.body, "<html>Conflict</html>");
+///         },
+///         other => panic!("expected a conflict, got {:?}", other),
+///     }
+///     match streak::api::pipelines::get(&client, &"b".into()) {
+///         Err(StreakError::HttpStatus(e)) => assert_eq!(e.status, 418),
+///         other => panic!("expected an http status error, got {:?}", other),
+///     }
+///     match streak::api::pipelines::get(&client, &"c".into()) {
+///         Err(StreakError::NotFound { resource, .. }) => assert_eq!(resource, "pipelines/c"),
+///         other => panic!("expected a missing pipeline, got {:?}", other),
+///     }
+///     assert_eq!(streak::api::pipelines::get(&client, &"a".into()).unwrap_err(),
+///                streak::api::pipelines::get(&client, &"a".into()).unwrap_err());
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StreakError {
     /// There was an error with the request.
-    BadRequest(Status), // 400
+    BadRequest(ApiError), // 400
 
     /// Either the API key or the verification token was invalid.
-    UnauthorizedKey(Status), // 401
+    UnauthorizedKey(ApiError), // 401
 
     /// This account does not have access to the requested service.
-    Forbidden(Status), // 403
+    Forbidden(ApiError), // 403
 
-    /// The authy user could not be found
-    UserNotFound(Status), // 404
+    /// The requested resource, such as `boxes/<box key>`, does not exist.
+    NotFound { resource: String, error: ApiError }, // 404
+
+    /// The request conflicts with the current state of the resource.
+    Conflict(ApiError), // 409
+
+    /// Too many requests were made in too short a time. `retry_after` is how
+    /// long the service asked us to wait, if it said so.
+    RateLimited { retry_after: Option<Duration>, error: ApiError }, // 429
 
     /// There was an internal server error.
-    InternalServerError(Status), // 500
+    InternalServerError(ApiError), // 500
+
+    /// A gateway in front of the service received an invalid response from it.
+    BadGateway(ApiError), // 502
+
+    /// The service was unavailable. Only returned once the retry policy gives up.
+    ServiceUnavailable(ApiError), // 503
+
+    /// A gateway in front of the service timed out waiting for it.
+    GatewayTimeout(ApiError), // 504
 
-    /// The authy service was unavailable. Only returned after the configured `retry_count`.
-    ServiceUnavailable, // 503
+    /// The service answered with an error status not covered by another variant.
+    HttpStatus(ApiError),
 
     /// There was an IO error.
-    IoError(String),
+    IoError(Source<io::Error>),
 
     /// There was an error deserializing a json object.
-    JsonParseError(String),
+    JsonParseError(Source<serde_json::Error>),
 
     /// We made a request the server didn't like.
-    RequestError(String),
+    RequestError(Source<reqwest::Error>),
 
     /// We made a request with a bad url
-    RequestUrlError(String),
+    RequestUrlError(reqwest::UrlError),
 
-    /// We made a request with a bad url
-    RequestUrlEncodeError(String),
+    /// We could not encode the parameters of a request.
+    RequestUrlEncodeError(Source<serde_url_params::Error>),
+
+    /// The server reported success, but its response could not be understood.
+    InvalidServerResponse(ApiError),
+
+    /// The service answered a delete with `success: false`. Holds the
+    /// resource, such as `boxes/<box key>`.
+    NotDeleted(String),
+
+    /// A pipeline schema could not be read, or does not fit the account it is
+    /// applied to.
+    InvalidSchema(String),
+
+    /// A Box field does not exist, or can not hold the value it was given, or
+    /// a Field is created with settings its type does not take.
+    InvalidFieldValue(String),
+
+    /// A Box can not move to the Stage it was asked to, or a `StageRules`
+    /// rule forbids the move.
+    InvalidStageMove(String),
+
+    /// A response has properties the models do not know about. Only raised
+    /// by `Model::strict`.
+    SchemaDrift(Vec<String>),
+
+    /// A client was configured with a setting it can not honor.
+    UnsupportedSetting(String),
+}
+
+/// An error raised by another library, shared so that `StreakError` can be
+/// cloned. Two sources are equal when they describe the same error.
+#[derive(Debug)]
+pub struct Source<T>(Arc<T>);
+
+impl<T> Source<T> {
+    /// Wrap `error`.
+    pub fn new(error: T) -> Source<T> {
+        Source(Arc::new(error))
+    }
+}
+
+impl<T> Clone for Source<T> {
+    fn clone(&self) -> Source<T> {
+        Source(self.0.clone())
+    }
+}
+
+impl<T> Deref for Source<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Source<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> PartialEq for Source<T> {
+    fn eq(&self, other: &Source<T>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl<T: fmt::Display> Eq for Source<T> {}
+
+/// A request the Streak service answered with an error, or with a response
+/// that could not be understood.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiError {
+    /// The method of the request.
+    pub method: Method,
 
-    /// The server gave an invalid response.
-    InvalidServerResponse,
+    /// The path and query of the request, with the API key redacted.
+    pub path: String,
+
+    /// The HTTP status code of the response.
+    pub status: u16,
+
+    /// The status message of the response. When the body is not a `Status`
+    /// this holds the body itself.
+    pub message: Status,
+
+    /// The raw body of the response.
+    pub body: String,
+}
+
+impl ApiError {
+    /// Describe the response to a `method` request for `path`.
+    pub fn new(method: Method, path: &str, status: u16, body: &str) -> ApiError {
+        // Error bodies are usually a `Status`, but proxies and load balancers
+        // in front of the service answer with plain text or HTML.
+        let message = serde_json::from_str(body).unwrap_or_else(|_| Status {
+            code: Some(status.into()),
+            error: body.trim().into(),
+        });
+
+        ApiError {
+            method: method,
+            path: path.into(),
+            status: status,
+            message: message,
+            body: body.into(),
+        }
+    }
+
+    /// The resource the request was about: the part of the path after the
+    /// API version, such as `boxes/<box key>`.
+    pub fn resource(&self) -> &str {
+        let path = self.path.split('?').next().unwrap_or("");
+        for version in &["/v1/", "/v2/"] {
+            if let Some(i) = path.find(version) {
+                return path[i + version.len()..].trim_matches('/');
+            }
+        }
+        path.trim_matches('/')
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} {} returned {})", self.message.error, self.method, self.path, self.status)
+    }
+}
+
+impl StreakError {
+    /// Whether the error is likely to go away by itself, so that sending the
+    /// same request again may succeed. This covers rate limiting, server side
+    /// failures and network errors.
+    pub fn is_transient(&self) -> bool {
+        use StreakError::*;
+        match *self {
+            RateLimited { .. } |
+            InternalServerError(_) |
+            BadGateway(_) |
+            ServiceUnavailable(_) |
+            GatewayTimeout(_) |
+            IoError(_) |
+            RequestError(_) => true,
+            _ => false,
+        }
+    }
+
+    /// How long the service asked us to wait before trying again.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match *self {
+            StreakError::RateLimited { retry_after, .. } => retry_after,
+            _ => None,
+        }
+    }
+
+    /// The failed request and the response to it, when the error came from
+    /// the Streak service.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        use StreakError::*;
+        match *self {
+            BadRequest(ref e) |
+            UnauthorizedKey(ref e) |
+            Forbidden(ref e) |
+            NotFound { error: ref e, .. } |
+            Conflict(ref e) |
+            RateLimited { error: ref e, .. } |
+            InternalServerError(ref e) |
+            BadGateway(ref e) |
+            ServiceUnavailable(ref e) |
+            GatewayTimeout(ref e) |
+            HttpStatus(ref e) |
+            InvalidServerResponse(ref e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl error::Error for StreakError {
@@ -55,19 +282,39 @@ impl error::Error for StreakError {
             BadRequest(_) => "400 bad request",
             UnauthorizedKey(_) => "401 unauthorized",
             Forbidden(_) => "403 forbidden",
-            UserNotFound(_) => "404 not found",
+            NotFound { .. } => "404 not found",
+            Conflict(_) => "409 conflict",
+            RateLimited { .. } => "429 too many requests",
             InternalServerError(_) => "500 internal server error",
-            ServiceUnavailable => "503 service unavailable",
+            BadGateway(_) => "502 bad gateway",
+            ServiceUnavailable(_) => "503 service unavailable",
+            GatewayTimeout(_) => "504 gateway timeout",
+            HttpStatus(_) => "unexpected HTTP status",
             IoError(_) => "IO error",
             JsonParseError(_) => "JSON parse error",
             RequestError(_) => "Request error",
             RequestUrlError(_) => "Request URL error",
             RequestUrlEncodeError(_) => "Request URL Encode error",
-            InvalidServerResponse => "Invalid server response",
+            InvalidServerResponse(_) => "Invalid server response",
+            NotDeleted(_) => "Not deleted",
+            InvalidSchema(_) => "Invalid pipeline schema",
+            InvalidFieldValue(_) => "Invalid field value",
+            InvalidStageMove(_) => "Invalid stage move",
+            SchemaDrift(_) => "Schema drift",
+            UnsupportedSetting(_) => "Unsupported setting",
         }
     }
-    fn cause(&self) -> Option<&error::Error> {
-        None
+
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use StreakError::*;
+        match *self {
+            IoError(ref e) => Some(&**e),
+            JsonParseError(ref e) => Some(&**e),
+            RequestError(ref e) => Some(&**e),
+            RequestUrlError(ref e) => Some(e),
+            RequestUrlEncodeError(ref e) => Some(&**e),
+            _ => None,
+        }
     }
 }
 
@@ -76,48 +323,60 @@ impl fmt::Display for StreakError {
         use StreakError::*;
 
         match *self {
-            BadRequest(ref s) => write!(f, "Bad Request: {}", s.error),
-            UnauthorizedKey(ref s) => write!(f, "Unauthorized API Key: {}", s.error),
-            Forbidden(ref s) => write!(f, "Forbidden: {}", s.error),
-            UserNotFound(ref s) => write!(f, "User Not Found: {}", s.error),
-            InternalServerError(ref s) => write!(f, "Internal Server Error: {}", s.error),
-            ServiceUnavailable => write!(f, "Service Unavailable reported by authy service"),
-            IoError(ref s) => write!(f, "IO Error: {}", s),
-            JsonParseError(ref s) => write!(f, "Json parsing error: {}", s),
-            RequestError(ref s) => write!(f, "Request error: {}", s),
-            RequestUrlError(ref s) => write!(f, "Bad Request URL: {}", s),
-            RequestUrlEncodeError(ref s) => write!(f, "Bad Request URL Encoding: {}", s),
-            InvalidServerResponse => write!(f, "Server returned an invalid response"),
+            BadRequest(ref e) => write!(f, "Bad Request: {}", e),
+            UnauthorizedKey(ref e) => write!(f, "Unauthorized API Key: {}", e),
+            Forbidden(ref e) => write!(f, "Forbidden: {}", e),
+            NotFound { ref resource, ref error } => write!(f, "Not Found: {}: {}", resource, error),
+            Conflict(ref e) => write!(f, "Conflict: {}", e),
+            RateLimited { retry_after: Some(ref d), ref error } => write!(f, "Rate Limited, retry after {}s: {}", d.as_secs(), error),
+            RateLimited { retry_after: None, ref error } => write!(f, "Rate Limited: {}", error),
+            InternalServerError(ref e) => write!(f, "Internal Server Error: {}", e),
+            BadGateway(ref e) => write!(f, "Bad Gateway: {}", e),
+            ServiceUnavailable(ref e) => write!(f, "Service Unavailable: {}", e),
+            GatewayTimeout(ref e) => write!(f, "Gateway Timeout: {}", e),
+            HttpStatus(ref e) => write!(f, "Unexpected HTTP Status: {}", e),
+            IoError(ref e) => write!(f, "IO Error: {}", e),
+            JsonParseError(ref e) => write!(f, "Json parsing error: {}", e),
+            RequestError(ref e) => write!(f, "Request error: {}", e),
+            RequestUrlError(ref e) => write!(f, "Bad Request URL: {}", e),
+            RequestUrlEncodeError(ref e) => write!(f, "Bad Request URL Encoding: {}", e),
+            InvalidServerResponse(ref e) => write!(f, "Server returned an invalid response: {}", e),
+            NotDeleted(ref resource) => write!(f, "The service did not delete {}", resource),
+            InvalidSchema(ref e) => write!(f, "Invalid pipeline schema: {}", e),
+            InvalidFieldValue(ref e) => write!(f, "Invalid field value: {}", e),
+            InvalidStageMove(ref e) => write!(f, "Invalid stage move: {}", e),
+            SchemaDrift(ref fields) => write!(f, "Schema drift, unknown fields: {}", fields.join(", ")),
+            UnsupportedSetting(ref e) => write!(f, "Unsupported setting: {}", e),
         }
     }
 }
 
 impl From<reqwest::Error> for StreakError {
     fn from(e: reqwest::Error) -> Self {
-        StreakError::RequestError(e.to_string())
+        StreakError::RequestError(Source::new(e))
     }
 }
 
 impl From<reqwest::UrlError> for StreakError {
     fn from(e: reqwest::UrlError) -> Self {
-        StreakError::RequestUrlError(e.to_string())
+        StreakError::RequestUrlError(e)
     }
 }
 
 impl From<serde_json::Error> for StreakError {
     fn from(e: serde_json::Error) -> Self {
-        StreakError::JsonParseError(e.to_string())
+        StreakError::JsonParseError(Source::new(e))
     }
 }
 
 impl From<io::Error> for StreakError {
     fn from(e: io::Error) -> Self {
-        StreakError::IoError(e.to_string())
+        StreakError::IoError(Source::new(e))
     }
 }
 
 impl From<serde_url_params::Error> for StreakError {
     fn from(e: serde_url_params::Error) -> Self {
-        StreakError::RequestUrlError(e.to_string())
+        StreakError::RequestUrlEncodeError(Source::new(e))
     }
 }
@@ -18,18 +18,46 @@
 //!
 //! Create a client and then access any of the endpoints
 //!
-//! ```rust
+//! ```rust,no_run
 //! extern crate streak;
 //!
 //! use streak::Client;
 //! use std::env;
 //!
 //! fn main() {
-//! #   streak::Client::example();
 //!     let api_key = env::var("STREAK_API_KEY").expect("to have a STREAK_API_KEY set");
 //!     let client = Client::new(&api_key);
 //! }
 //! ```
+//!
+//! ## Async
+//!
+//! Enable the `async` feature to get an `AsyncClient` built on futures and
+//! tokio. Every endpoint has an `_async` counterpart that returns a
+//! `StreakFuture` instead of blocking.
+//!
+//! ```toml
+//! [dependencies]
+//! streak = { version = "*", features = ["async"] }
+//! ```
+//!
+//! ## Pipeline schemas
+//!
+//! Enable the `schema` feature to describe pipelines, their stages and their
+//! fields in a TOML or YAML file, and bring an account in line with it. See
+//! the `schema` module.
+//!
+//! ## Dates
+//!
+//! Every date and timestamp the API returns is an `api::Timestamp`, which
+//! keeps the milliseconds since the Unix epoch. Enable the `chrono` feature
+//! to turn them into `chrono::DateTime<Utc>` with `Timestamp::to_datetime`,
+//! and back.
+//!
+//! ## Testing
+//!
+//! `testing::FakeStreak` runs an in-memory Streak service on a local port, so
+//! code using this library can be tested end to end without an account.
 
 extern crate reqwest;
 extern crate serde;
@@ -42,11 +70,42 @@ extern crate serde_url_params;
 extern crate log;
 
 extern crate dotenv;
+extern crate rand;
+
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "async")]
+extern crate tokio_core;
+
+#[cfg(feature = "schema")]
+extern crate serde_yaml;
+#[cfg(feature = "schema")]
+extern crate toml;
+#[cfg(feature = "chrono")]
+extern crate chrono;
 
 pub mod error;
-pub use error::StreakError;
+pub use error::{ApiError, StreakError};
 
 mod client;
-pub use client::{Client, Status};
+pub use client::{Client, ClientBuilder, RequestBody, Status};
+
+pub mod endpoint;
+pub use endpoint::Endpoint;
+
+#[cfg(feature = "async")]
+mod async_client;
+#[cfg(feature = "async")]
+pub use async_client::{AsyncClient, StreakFuture, StreakStream};
+
+pub mod cassette;
+pub mod drift;
+pub mod pagination;
+pub mod retry;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod streaming;
+pub mod testing;
+pub mod transport;
 
 pub mod api;
@@ -0,0 +1,111 @@
+//! Listings that span several pages.
+//!
+//! Large listings, such as the Boxes of a busy Pipeline or the results of a
+//! broad search, are served a page at a time. `Paginated` iterates over the
+//! items of every page, and only fetches a page once the items of the one
+//! before it have been used up, so the whole listing never has to be held in
+//! memory at once. With the `async` feature the same listings are available as
+//! a `StreakStream`.
+
+use std::fmt;
+use std::vec;
+
+#[cfg(feature = "async")]
+use futures::{stream, Future, Stream};
+
+#[cfg(feature = "async")]
+use async_client::StreakStream;
+use error::StreakError;
+
+/// One page of a listing.
+#[derive(Debug)]
+pub(crate) struct Page<T> {
+    pub items: Vec<T>,
+    /// Whether another page may follow. A listing also ends at the first
+    /// empty page.
+    pub more: bool,
+}
+
+/// An iterator over the items of a listing, fetching its pages as needed.
+///
+/// A request that fails is yielded as an `Err`, after which the iterator
+/// ends.
+pub struct Paginated<'a, T> {
+    next_page: Box<dyn FnMut(u32) -> Result<Page<T>, StreakError> + 'a>,
+    page: u32,
+    items: vec::IntoIter<T>,
+    done: bool,
+}
+
+impl<'a, T> Paginated<'a, T> {
+    /// Iterate over the pages `next_page` returns for page numbers starting
+    /// from 0.
+    pub(crate) fn new<F>(next_page: F) -> Paginated<'a, T>
+        where F: FnMut(u32) -> Result<Page<T>, StreakError> + 'a
+    {
+        Paginated {
+            next_page: Box::new(next_page),
+            page: 0,
+            items: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Paginated<'a, T> {
+    type Item = Result<T, StreakError>;
+
+    fn next(&mut self) -> Option<Result<T, StreakError>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+
+            debug!("Fetching page {}", self.page);
+            match (self.next_page)(self.page) {
+                Ok(page) => {
+                    self.page += 1;
+                    self.done = !page.more || page.items.is_empty();
+                    self.items = page.items.into_iter();
+                },
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for Paginated<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Paginated")
+            .field("page", &self.page)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+/// A stream of the items of the pages `next_page` resolves to for page
+/// numbers starting from 0. Like `Paginated`, the stream ends after an error.
+#[cfg(feature = "async")]
+pub(crate) fn stream<T, F, P>(next_page: F) -> StreakStream<T>
+    where T: 'static,
+          F: FnMut(u32) -> P + 'static,
+          P: Future<Item = Page<T>, Error = StreakError> + 'static
+{
+    let pages = stream::unfold((next_page, 0, false), |(mut next_page, page, done)| {
+        if done {
+            return None;
+        }
+        debug!("Fetching page {}", page);
+        Some(next_page(page).map(move |p| {
+            let done = !p.more || p.items.is_empty();
+            (stream::iter_ok(p.items), (next_page, page + 1, done))
+        }))
+    });
+    Box::new(pages.flatten())
+}
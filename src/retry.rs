@@ -0,0 +1,139 @@
+//! Policies deciding whether a failed request is sent again, and when.
+
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+
+use rand::{self, Rng};
+
+use error::StreakError;
+
+/// Decides whether a failed request should be retried.
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// How long to wait before sending a request again, after its `attempt`th
+    /// try failed with `error`. Attempts are counted from 1. `idempotent`
+    /// tells whether sending the request twice has the same effect as
+    /// sending it once, as `Endpoint::is_idempotent` decides. Returning
+    /// `None` gives up and hands `error` to the caller.
+    fn retry_delay(&self, idempotent: bool, attempt: u32, error: &StreakError) -> Option<Duration>;
+}
+
+/// Never retry a request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry_delay(&self, _: bool, _: u32, _: &StreakError) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retry with exponentially growing delays.
+///
+/// The delay starts at `initial_delay`, is multiplied by `multiplier` after
+/// every attempt and never exceeds `max_delay`. With `jitter` enabled a random
+/// amount of up to half the delay is taken off, so that many clients failing
+/// at once do not all come back at the same moment. When the service sends a
+/// `Retry-After` delay it is used instead.
+///
+/// By default only errors for which `StreakError::is_transient` holds are
+/// retried, and only for idempotent requests. A request is sent at most
+/// `max_attempts` times in total.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use std::time::Duration;
+/// use streak::StreakError;
+/// use streak::error::ApiError;
+/// use streak::retry::{ExponentialBackoff, RetryPolicy};
+/// use streak::transport::Method;
+///
+/// fn main() {
+///     let policy = ExponentialBackoff {
+///         jitter: false,
+///         .. ExponentialBackoff::default()
+///     };
+///     let error = StreakError::ServiceUnavailable(ApiError::new(Method::Get, "/api/v1/pipelines", 503, ""));
+///
+///     assert_eq!(policy.retry_delay(true, 1, &error), Some(Duration::from_millis(250)));
+///     assert_eq!(policy.retry_delay(true, 2, &error), Some(Duration::from_millis(500)));
+///     assert_eq!(policy.retry_delay(true, 3, &error), None);
+///     assert_eq!(policy.retry_delay(false, 1, &error), None);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    /// How many times in total a request is sent before giving up, counting
+    /// the first try.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry.
+    pub initial_delay: Duration,
+
+    /// The longest delay between two attempts, including `Retry-After` delays.
+    pub max_delay: Duration,
+
+    /// The factor the delay grows by after every attempt.
+    pub multiplier: u32,
+
+    /// Randomize the delays.
+    pub jitter: bool,
+
+    /// Also retry requests that are not idempotent, such as those creating a
+    /// Box. A request that failed after reaching the service may then be
+    /// carried out twice.
+    pub retry_non_idempotent: bool,
+
+    /// Which errors are worth retrying.
+    pub retryable: fn(&StreakError) -> bool,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> ExponentialBackoff {
+        ExponentialBackoff {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2,
+            jitter: true,
+            retry_non_idempotent: false,
+            retryable: StreakError::is_transient,
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let mut delay = self.initial_delay;
+        for _ in 1..attempt {
+            delay = match delay.checked_mul(self.multiplier) {
+                Some(d) if d < self.max_delay => d,
+                _ => return self.max_delay,
+            };
+        }
+        cmp::min(delay, self.max_delay)
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, idempotent: bool, attempt: u32, error: &StreakError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(self.retryable)(error) {
+            return None;
+        }
+        if !self.retry_non_idempotent && !idempotent {
+            return None;
+        }
+        if let Some(retry_after) = error.retry_after() {
+            return Some(cmp::min(retry_after, self.max_delay));
+        }
+
+        let delay = self.backoff(attempt);
+        if !self.jitter {
+            return Some(delay);
+        }
+        let millis = delay.as_secs() * 1000 + u64::from(delay.subsec_nanos() / 1_000_000);
+        let jitter = rand::thread_rng().gen_range(0, millis / 2 + 1);
+        Some(Duration::from_millis(millis - jitter))
+    }
+}
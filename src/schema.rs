@@ -0,0 +1,631 @@
+//! Pipelines described as code.
+//!
+//! A `Schema` lists pipelines with their stages, fields and dropdown items,
+//! usually read from a TOML or YAML file. `Schema::plan` compares it with the
+//! pipelines on an account and returns the `Plan` of changes that would bring
+//! the account in line with it, which `Plan::apply` then carries out.
+//!
+//! Pipelines, stages, fields and items are matched by name. Nothing that can
+//! hold data is deleted unless `prune` is set: without it, stages and fields
+//! a described pipeline has but the schema does not list stay, and so do
+//! fields whose type differs, since changing the type of a field means
+//! deleting it along with its values. Those changes are listed as held back
+//! in the plan instead. Pipelines the schema does not describe are left
+//! alone unless the caller sets `prune_pipelines`, which a schema file can
+//! not do.
+//!
+//! ```rust
+//! extern crate streak;
+//!
+//! use streak::schema::Schema;
+//! use streak::testing::FakeStreak;
+//!
+//! const SCHEMA: &str = r##"
+//! [[pipelines]]
+//! name = "Partner Program"
+//! description = "Partners signed up this year"
+//!
+//! [[pipelines.stages]]
+//! name = "Applied"
+//!
+//! [[pipelines.stages]]
+//! name = "Active"
+//! color = { foregroundColor = "#ffffff", backgroundColor = "#6aa84f" }
+//!
+//! [[pipelines.fields]]
+//! name = "Region"
+//! type = "DROPDOWN"
+//! items = ["EMEA", "APAC", "Americas"]
+//! "##;
+//!
+//! fn main() {
+//!     let streak = FakeStreak::start().expect("to start the fake service");
+//!     let client = streak.client();
+//!     let schema = Schema::from_toml(SCHEMA).expect("a valid schema");
+//!
+//!     let plan = schema.apply(&client, true).expect("plan the changes");
+//!     println!("{}", plan);
+//!     assert!(!plan.is_empty());
+//!     assert!(streak::api::pipelines::list(&client).expect("list pipelines").is_empty());
+//!
+//!     schema.apply(&client, false).expect("apply the changes");
+//!     let pipelines = streak::api::pipelines::list(&client).expect("list pipelines");
+//!     assert_eq!(pipelines[0].name, "Partner Program");
+//!     assert_eq!(pipelines[0].fields[0].items().len(), 3);
+//!
+//!     assert!(schema.apply(&client, true).expect("plan again").is_empty());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde_yaml;
+use toml;
+
+use api::{PipelineKey, StageKey};
+use api::pipelines::{self, fields, stages, Color, Field, FieldItem, FieldType, Pipeline};
+use client::Client;
+use error::StreakError;
+
+/// The pipelines an account should have.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    /// Also delete the stages and fields of the described pipelines that are
+    /// not listed, and replace the fields whose type differs. Other pipelines
+    /// are not touched.
+    #[serde(default)]
+    pub prune: bool,
+
+    /// Also delete the pipelines on the account that are not described here.
+    /// Never read from a schema file, so only the caller can set it.
+    #[serde(skip)]
+    pub prune_pipelines: bool,
+
+    #[serde(default)]
+    pub pipelines: Vec<PipelineSchema>,
+}
+
+/// A pipeline, its stages in order, and its fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineSchema {
+    pub name: String,
+
+    /// Left as it is when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub stages: Vec<StageSchema>,
+
+    #[serde(default)]
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageSchema {
+    pub name: String,
+
+    /// Left as it is when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+
+    /// The type, as the Streak API names it, such as `TEXT_INPUT` or
+    /// `DROPDOWN`.
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+
+    /// The names of the choices of a dropdown field or the tags of a tag
+    /// field, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<String>,
+
+    /// The formula of a formula field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula: Option<String>,
+}
+
+/// The changes needed to bring an account in line with a `Schema`, in the
+/// order they are applied.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::api::pipelines::{self, FieldType};
+/// use streak::schema::Schema;
+/// use streak::testing::FakeStreak;
+///
+/// const SCHEMA: &str = r##"
+/// [[pipelines]]
+/// name = "Sales"
+///
+/// [[pipelines.stages]]
+/// name = "Lead"
+///
+/// [[pipelines.fields]]
+/// name = "Close Date"
+/// type = "DATE"
+/// "##;
+///
+/// fn main() {
+///     let streak = FakeStreak::start().expect("to start the fake service");
+///     let pipeline = streak.add_pipeline("Sales", &["Lead", "Lost"]);
+///     streak.add_pipeline("Hiring", &["Applied"]);
+///     streak.add_field(&pipeline.pipeline_key, "Close Date", FieldType::Text);
+///     streak.add_field(&pipeline.pipeline_key, "Notes", FieldType::Text);
+///     let client = streak.client();
+///     let mut schema = Schema::from_toml(SCHEMA).expect("a valid schema");
+///
+///     let plan = schema.apply(&client, false).expect("apply the changes");
+///     assert!(plan.is_empty());
+///     assert_eq!(plan.held_back.len(), 3);
+///     assert!(plan.to_string().contains("forces replacement"));
+///     let live = pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///     assert_eq!((live.stages.len(), live.fields.len()), (2, 2));
+///
+///     schema.prune = true;
+///     let plan = schema.apply(&client, false).expect("apply the changes");
+///     assert!(plan.held_back.is_empty());
+///     let live = pipelines::get(&client, &pipeline.pipeline_key).expect("get the pipeline");
+///     assert_eq!((live.stages.len(), live.fields.len()), (1, 1));
+///     assert_eq!(live.fields[0].field_type, FieldType::Date);
+///     assert_eq!(pipelines::list(&client).expect("list pipelines").len(), 2);
+///
+///     schema.prune_pipelines = true;
+///     schema.apply(&client, false).expect("apply the changes");
+///     assert_eq!(pipelines::list(&client).expect("list pipelines").len(), 1);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+
+    /// The deletions and replacements the schema calls for that were left
+    /// out because `prune` is not set. They are shown, but not applied.
+    pub held_back: Vec<Change>,
+}
+
+/// A single change of a `Plan`. Pipelines, stages and fields are referred to
+/// by name, since the ones still to be created have no key yet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    CreatePipeline { name: String, description: Option<String>, stages: Vec<String> },
+    UpdatePipeline { name: String, description: String },
+    /// Only planned with `prune_pipelines`.
+    DeletePipeline { name: String },
+    CreateStage { pipeline: String, name: String },
+    UpdateStage { pipeline: String, name: String, color: Color },
+    /// Only planned with `prune`.
+    DeleteStage { pipeline: String, name: String },
+    ReorderStages { pipeline: String, order: Vec<String> },
+    CreateField { pipeline: String, name: String, field_type: FieldType, items: Vec<String>, formula: Option<String> },
+    UpdateField { pipeline: String, name: String, items: Option<Vec<String>>, formula: Option<String> },
+    /// Only planned with `prune`. The values of the field are lost.
+    DeleteField { pipeline: String, name: String },
+    /// Delete a field and create it again with another type, which the
+    /// Streak API can not change in place. Only planned with `prune`. The
+    /// values of the field are lost.
+    ReplaceField { pipeline: String, name: String, from: FieldType, field_type: FieldType, items: Vec<String>, formula: Option<String> },
+}
+
+impl Schema {
+    /// Read a schema from a TOML document.
+    pub fn from_toml(document: &str) -> Result<Schema, StreakError> {
+        toml::from_str(document).map_err(|e| StreakError::InvalidSchema(e.to_string()))
+    }
+
+    /// Read a schema from a YAML document.
+    pub fn from_yaml(document: &str) -> Result<Schema, StreakError> {
+        serde_yaml::from_str(document).map_err(|e| StreakError::InvalidSchema(e.to_string()))
+    }
+
+    /// Read a schema from a `.toml`, `.yaml` or `.yml` file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Schema, StreakError> {
+        let path = path.as_ref();
+        let mut document = String::new();
+        File::open(path)?.read_to_string(&mut document)?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Schema::from_toml(&document),
+            Some("yaml") | Some("yml") => Schema::from_yaml(&document),
+            _ => Err(StreakError::InvalidSchema(format!("{} is neither a TOML nor a YAML file", path.display()))),
+        }
+    }
+
+    /// The changes that would turn the `live` pipelines into the ones this
+    /// schema describes.
+    pub fn plan(&self, live: &[Pipeline]) -> Plan {
+        let mut plan = Plan::default();
+
+        for spec in &self.pipelines {
+            match live.iter().find(|p| p.name == spec.name) {
+                Some(pipeline) => plan_pipeline(spec, pipeline, self.prune, &mut plan),
+                None => plan_new_pipeline(spec, &mut plan.changes),
+            }
+        }
+
+        if self.prune_pipelines {
+            for pipeline in live {
+                if !self.pipelines.iter().any(|p| p.name == pipeline.name) {
+                    plan.changes.push(Change::DeletePipeline { name: pipeline.name.clone() });
+                }
+            }
+        }
+
+        plan
+    }
+
+    /// Compare the schema with the pipelines on the account of `client`, and
+    /// apply the resulting plan unless `dry_run` is set. Returns the plan
+    /// either way.
+    pub fn apply(&self, client: &Client, dry_run: bool) -> Result<Plan, StreakError> {
+        let plan = self.plan(&pipelines::list(client)?);
+        if !dry_run {
+            plan.apply(client)?;
+        }
+        Ok(plan)
+    }
+}
+
+fn plan_new_pipeline(spec: &PipelineSchema, changes: &mut Vec<Change>) {
+    changes.push(Change::CreatePipeline {
+        name: spec.name.clone(),
+        description: spec.description.clone(),
+        stages: spec.stages.iter().map(|s| s.name.clone()).collect(),
+    });
+    for stage in &spec.stages {
+        if let Some(ref color) = stage.color {
+            changes.push(Change::UpdateStage {
+                pipeline: spec.name.clone(),
+                name: stage.name.clone(),
+                color: color.clone(),
+            });
+        }
+    }
+    for field in &spec.fields {
+        changes.push(create_field(&spec.name, field));
+    }
+}
+
+fn plan_pipeline(spec: &PipelineSchema, pipeline: &Pipeline, prune: bool, plan: &mut Plan) {
+    match spec.description {
+        Some(ref description) if pipeline.description.as_ref() != Some(description) => {
+            plan.changes.push(Change::UpdatePipeline {
+                name: spec.name.clone(),
+                description: description.clone(),
+            });
+        },
+        _ => {},
+    }
+
+    plan_stages(spec, pipeline, prune, plan);
+    plan_fields(spec, pipeline, prune, plan);
+}
+
+fn plan_stages(spec: &PipelineSchema, pipeline: &Pipeline, prune: bool, plan: &mut Plan) {
+    let live: Vec<_> = pipeline.stage_order.iter().filter_map(|key| pipeline.stages.get(key)).collect();
+    let wanted: Vec<String> = spec.stages.iter().map(|s| s.name.clone()).collect();
+
+    for stage in &spec.stages {
+        let existing = live.iter().find(|s| s.name == stage.name);
+        if existing.is_none() {
+            plan.changes.push(Change::CreateStage {
+                pipeline: spec.name.clone(),
+                name: stage.name.clone(),
+            });
+        }
+        match stage.color {
+            Some(ref color) if existing.map_or(true, |s| s.color != *color) => {
+                plan.changes.push(Change::UpdateStage {
+                    pipeline: spec.name.clone(),
+                    name: stage.name.clone(),
+                    color: color.clone(),
+                });
+            },
+            _ => {},
+        }
+    }
+    for stage in &live {
+        if !wanted.contains(&stage.name) {
+            plan.destroy(prune, Change::DeleteStage {
+                pipeline: spec.name.clone(),
+                name: stage.name.clone(),
+            });
+        }
+    }
+
+    // New stages end up last, so the order only needs fixing when the listed
+    // stages are out of order, or a new one belongs before them. Unlisted
+    // stages that are kept go after the listed ones, since a reorder has to
+    // name every stage.
+    let mut order: Vec<String> = live.iter()
+        .map(|s| s.name.clone())
+        .filter(|name| !prune || wanted.contains(name))
+        .collect();
+    order.extend(wanted.iter().filter(|name| !order.contains(name)).cloned().collect::<Vec<_>>());
+    let listed: Vec<&String> = order.iter().filter(|name| wanted.contains(name)).collect();
+    if listed != wanted.iter().collect::<Vec<_>>() {
+        let unlisted: Vec<String> = order.iter().filter(|name| !wanted.contains(name)).cloned().collect();
+        let mut order = wanted;
+        order.extend(unlisted);
+        plan.changes.push(Change::ReorderStages {
+            pipeline: spec.name.clone(),
+            order: order,
+        });
+    }
+}
+
+fn plan_fields(spec: &PipelineSchema, pipeline: &Pipeline, prune: bool, plan: &mut Plan) {
+    for field in &spec.fields {
+        let existing = match pipeline.fields.iter().find(|f| f.name == field.name) {
+            Some(existing) => existing,
+            None => {
+                plan.changes.push(create_field(&spec.name, field));
+                continue;
+            },
+        };
+
+        // The type of a field can not be changed, it has to be created again.
+        if existing.field_type != field.field_type {
+            plan.destroy(prune, Change::ReplaceField {
+                pipeline: spec.name.clone(),
+                name: field.name.clone(),
+                from: existing.field_type.clone(),
+                field_type: field.field_type.clone(),
+                items: field.items.clone(),
+                formula: field.formula.clone(),
+            });
+            continue;
+        }
+
+        let live_items: Vec<&str> = existing.items().iter().map(|i| i.name.as_str()).collect();
+        let items = if live_items != field.items {
+            Some(field.items.clone())
+        } else {
+            None
+        };
+        let live_formula = existing.formula_settings.as_ref().map(|s| &s.formula);
+        let formula = match field.formula {
+            Some(ref formula) if live_formula != Some(formula) => Some(formula.clone()),
+            _ => None,
+        };
+        if items.is_some() || formula.is_some() {
+            plan.changes.push(Change::UpdateField {
+                pipeline: spec.name.clone(),
+                name: field.name.clone(),
+                items: items,
+                formula: formula,
+            });
+        }
+    }
+
+    for field in &pipeline.fields {
+        if !spec.fields.iter().any(|f| f.name == field.name) {
+            plan.destroy(prune, Change::DeleteField {
+                pipeline: spec.name.clone(),
+                name: field.name.clone(),
+            });
+        }
+    }
+}
+
+fn create_field(pipeline: &str, field: &FieldSchema) -> Change {
+    Change::CreateField {
+        pipeline: pipeline.into(),
+        name: field.name.clone(),
+        field_type: field.field_type.clone(),
+        items: field.items.clone(),
+        formula: field.formula.clone(),
+    }
+}
+
+impl Plan {
+    /// Whether there is nothing to apply. Changes held back for want of
+    /// `prune` do not count.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Plan a change that loses data, or hold it back without `prune`.
+    fn destroy(&mut self, prune: bool, change: Change) {
+        if prune {
+            self.changes.push(change);
+        } else {
+            self.held_back.push(change);
+        }
+    }
+
+    /// Carry out every change, in order. Stops at the first change that fails;
+    /// the ones before it stay applied.
+    pub fn apply(&self, client: &Client) -> Result<(), StreakError> {
+        let mut keys: HashMap<String, PipelineKey> = pipelines::list(client)?
+            .into_iter()
+            .map(|p| (p.name, p.pipeline_key))
+            .collect();
+
+        for change in &self.changes {
+            debug!("Applying schema change: {}", change);
+            apply_change(client, &mut keys, change)?;
+        }
+        Ok(())
+    }
+}
+
+fn apply_change(client: &Client, keys: &mut HashMap<String, PipelineKey>, change: &Change) -> Result<(), StreakError> {
+    match *change {
+        Change::CreatePipeline { ref name, ref description, ref stages } => {
+            let mut create = pipelines::create(name).stage_names(stages.clone());
+            if let Some(ref description) = *description {
+                create = create.description(description);
+            }
+            let pipeline = create.send(client)?;
+            keys.insert(name.clone(), pipeline.pipeline_key);
+        },
+        Change::UpdatePipeline { ref name, ref description } => {
+            pipelines::update(pipeline_key(keys, name)?).description(description).send(client)?;
+        },
+        Change::DeletePipeline { ref name } => {
+            pipelines::delete(client, pipeline_key(keys, name)?)?;
+            keys.remove(name);
+        },
+        Change::CreateStage { ref pipeline, ref name } => {
+            stages::create(client, pipeline_key(keys, pipeline)?, name)?;
+        },
+        Change::UpdateStage { ref pipeline, ref name, ref color } => {
+            let key = pipeline_key(keys, pipeline)?;
+            let stage_key = stage_keys(client, key)?.remove(name).ok_or_else(|| missing("stage", name))?;
+            stages::update(key, &stage_key).color(color.clone()).send(client)?;
+        },
+        Change::DeleteStage { ref pipeline, ref name } => {
+            let key = pipeline_key(keys, pipeline)?;
+            let stage_key = stage_keys(client, key)?.remove(name).ok_or_else(|| missing("stage", name))?;
+            stages::delete(client, key, &stage_key)?;
+        },
+        Change::ReorderStages { ref pipeline, ref order } => {
+            let key = pipeline_key(keys, pipeline)?;
+            let mut stage_keys = stage_keys(client, key)?;
+            let order = order.iter()
+                .map(|name| stage_keys.remove(name).ok_or_else(|| missing("stage", name)))
+                .collect::<Result<Vec<_>, _>>()?;
+            stages::reorder(client, key, order)?;
+        },
+        Change::CreateField { ref pipeline, ref name, ref field_type, ref items, ref formula } => {
+            let mut create = fields::create(pipeline_key(keys, pipeline)?, name, field_type.clone());
+            if !items.is_empty() {
+                create = create.items(&items.iter().map(|i| i.as_str()).collect::<Vec<_>>());
+            }
+            if let Some(ref formula) = *formula {
+                create = create.formula(formula);
+            }
+            create.send(client)?;
+        },
+        Change::UpdateField { ref pipeline, ref name, ref items, ref formula } => {
+            let key = pipeline_key(keys, pipeline)?;
+            let field = find_field(client, key, name)?;
+            let mut update = fields::update(key, &field.key);
+            if let Some(ref items) = *items {
+                // Keep the keys of the items that stay, so boxes keep their values.
+                let items = items.iter()
+                    .map(|name| field.item(name).cloned().unwrap_or_else(|| FieldItem::new(name)))
+                    .collect();
+                update = match field.field_type {
+                    FieldType::Tag => update.tag_items(items),
+                    _ => update.dropdown_items(items),
+                };
+            }
+            if let Some(ref formula) = *formula {
+                update = update.formula(formula);
+            }
+            update.send(client)?;
+        },
+        Change::DeleteField { ref pipeline, ref name } => {
+            let key = pipeline_key(keys, pipeline)?;
+            let field = find_field(client, key, name)?;
+            fields::delete(client, key, &field.key)?;
+        },
+        Change::ReplaceField { ref pipeline, ref name, ref field_type, ref items, ref formula, .. } => {
+            apply_change(client, keys, &Change::DeleteField {
+                pipeline: pipeline.clone(),
+                name: name.clone(),
+            })?;
+            apply_change(client, keys, &Change::CreateField {
+                pipeline: pipeline.clone(),
+                name: name.clone(),
+                field_type: field_type.clone(),
+                items: items.clone(),
+                formula: formula.clone(),
+            })?;
+        },
+    }
+    Ok(())
+}
+
+fn pipeline_key<'a>(keys: &'a HashMap<String, PipelineKey>, name: &str) -> Result<&'a PipelineKey, StreakError> {
+    keys.get(name).ok_or_else(|| missing("pipeline", name))
+}
+
+/// The keys of the stages of a pipeline, by name.
+fn stage_keys(client: &Client, pipeline_key: &PipelineKey) -> Result<HashMap<String, StageKey>, StreakError> {
+    Ok(stages::list(client, pipeline_key)?
+        .into_iter()
+        .map(|(key, stage)| (stage.name, key))
+        .collect())
+}
+
+fn find_field(client: &Client, pipeline_key: &PipelineKey, name: &str) -> Result<Field, StreakError> {
+    fields::list(client, pipeline_key)?
+        .into_iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| missing("field", name))
+}
+
+fn missing(kind: &str, name: &str) -> StreakError {
+    StreakError::InvalidSchema(format!("There is no {} called \"{}\"", kind, name))
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            writeln!(f, "No changes.")?;
+        }
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        if !self.held_back.is_empty() {
+            writeln!(f, "Held back, as prune is not set:")?;
+            for change in &self.held_back {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Change::*;
+        match *self {
+            CreatePipeline { ref name, ref stages, .. } => write!(f, "+ pipeline \"{}\" with stages {}", name, stages.join(", ")),
+            UpdatePipeline { ref name, ref description } => write!(f, "~ pipeline \"{}\" description: {}", name, description),
+            DeletePipeline { ref name } => write!(f, "- pipeline \"{}\"", name),
+            CreateStage { ref pipeline, ref name } => write!(f, "+ stage \"{}\" / \"{}\"", pipeline, name),
+            UpdateStage { ref pipeline, ref name, ref color } => {
+                write!(f, "~ stage \"{}\" / \"{}\" color: {} on {}", pipeline, name, color.foreground_color, color.background_color)
+            },
+            DeleteStage { ref pipeline, ref name } => write!(f, "- stage \"{}\" / \"{}\"", pipeline, name),
+            ReorderStages { ref pipeline, ref order } => write!(f, "~ stages of \"{}\" ordered: {}", pipeline, order.join(", ")),
+            CreateField { ref pipeline, ref name, ref field_type, ref items, .. } => {
+                write!(f, "+ field \"{}\" / \"{}\" {}", pipeline, name, field_type)?;
+                if !items.is_empty() {
+                    write!(f, " items: {}", items.join(", "))?;
+                }
+                Ok(())
+            },
+            UpdateField { ref pipeline, ref name, ref items, ref formula } => {
+                write!(f, "~ field \"{}\" / \"{}\"", pipeline, name)?;
+                if let Some(ref items) = *items {
+                    write!(f, " items: {}", items.join(", "))?;
+                }
+                if let Some(ref formula) = *formula {
+                    write!(f, " formula: {}", formula)?;
+                }
+                Ok(())
+            },
+            DeleteField { ref pipeline, ref name } => {
+                write!(f, "- field \"{}\" / \"{}\" (its values on every box are lost)", pipeline, name)
+            },
+            ReplaceField { ref pipeline, ref name, ref from, ref field_type, .. } => {
+                write!(f, "-/+ field \"{}\" / \"{}\" {} -> {} (forces replacement; its values on every box are lost)",
+                       pipeline, name, from, field_type)
+            },
+        }
+    }
+}
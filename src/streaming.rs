@@ -0,0 +1,127 @@
+//! Reading large JSON lists one item at a time.
+//!
+//! `JsonArray` deserializes the items of a JSON list straight from a reader,
+//! such as the body of a response, and hands them out one by one. Only the
+//! item being read is held in memory, however long the list is.
+//!
+//! ```rust
+//! extern crate streak;
+//!
+//! use streak::api::pipelines::Color;
+//! use streak::streaming::JsonArray;
+//!
+//! fn main() {
+//!     let json: &[u8] = br##"[
+//!         {"foregroundColor": "#ffffff", "backgroundColor": "#3d85c6"},
+//!         {"foregroundColor": "#000000", "backgroundColor": "#ffe599"}
+//!     ]"##;
+//!     let colors: Vec<Color> = JsonArray::new(json).collect::<Result<_, _>>().expect("read the colors");
+//!     assert_eq!(colors.len(), 2);
+//!     assert_eq!(colors[1].background_color, "#ffe599");
+//! }
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use error::StreakError;
+
+/// An iterator over the items of a JSON list, read from a reader as they are
+/// needed.
+///
+/// The items must be objects, lists or strings: a number has no end marker,
+/// so reading one would swallow the comma after it. An item that can not be
+/// read is yielded as an `Err`, after which the iterator ends.
+pub struct JsonArray<T> {
+    reader: BufReader<Box<dyn Read>>,
+    position: Position,
+    item: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Start,
+    First,
+    Rest,
+    Done,
+}
+
+impl<T: DeserializeOwned> JsonArray<T> {
+    pub fn new<R: Read + 'static>(reader: R) -> JsonArray<T> {
+        JsonArray {
+            reader: BufReader::new(Box::new(reader)),
+            position: Position::Start,
+            item: PhantomData,
+        }
+    }
+
+    fn next_item(&mut self) -> Result<Option<T>, StreakError> {
+        if self.position == Position::Start {
+            match self.peek()? {
+                Some(b'[') => self.reader.consume(1),
+                _ => return Err(invalid("Expected a JSON list")),
+            }
+            self.position = Position::First;
+        }
+
+        match (self.position, self.peek()?) {
+            (Position::Done, _) => return Ok(None),
+            (_, Some(b']')) => {
+                self.reader.consume(1);
+                self.position = Position::Done;
+                return Ok(None);
+            },
+            (Position::Rest, Some(b',')) => self.reader.consume(1),
+            (Position::First, Some(_)) => {},
+            _ => return Err(invalid("Expected a comma or the end of the JSON list")),
+        }
+        self.position = Position::Rest;
+
+        let mut de = serde_json::Deserializer::from_reader(&mut self.reader);
+        Ok(Some(T::deserialize(&mut de)?))
+    }
+
+    /// The next byte that is not whitespace, left unread.
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            let byte = match self.reader.fill_buf()?.first() {
+                Some(&byte) => byte,
+                None => return Ok(None),
+            };
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.reader.consume(1);
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for JsonArray<T> {
+    type Item = Result<T, StreakError>;
+
+    fn next(&mut self) -> Option<Result<T, StreakError>> {
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(e) => {
+                self.position = Position::Done;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+impl<T> fmt::Debug for JsonArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JsonArray")
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+fn invalid(message: &str) -> StreakError {
+    StreakError::from(io::Error::new(io::ErrorKind::InvalidData, message))
+}
@@ -0,0 +1,124 @@
+//! Just enough HTTP/1.1 to serve the requests a `Client` sends.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+
+use reqwest::Url;
+use serde_json::{self, Value};
+
+/// A request received by the fake server.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// The value of the header `name`, compared case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, ref v)| v.as_str())
+    }
+
+    /// Every value of the query parameter `name`.
+    pub fn params(&self, name: &str) -> Vec<&str> {
+        self.query.iter()
+            .filter(|&&(ref n, _)| n == name)
+            .map(|&(_, ref v)| v.as_str())
+            .collect()
+    }
+
+    /// The first value of the query parameter `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params(name).into_iter().next()
+    }
+
+    /// The parameters of a form encoded body.
+    pub fn form(&self) -> Vec<(String, String)> {
+        match Url::parse(&format!("http://fake.streak/?{}", self.body)) {
+            Ok(url) => url.query_pairs().into_owned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// The body parsed as JSON.
+    pub fn json(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(&self.body)
+    }
+}
+
+/// A response to send back.
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+pub fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_owned();
+    let target = parts.next().unwrap_or("/").to_owned();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(i) = line.find(':') {
+            headers.push((line[..i].trim().to_owned(), line[i + 1..].trim().to_owned()));
+        }
+    }
+
+    let length = headers.iter()
+        .find(|&&(ref n, _)| n.eq_ignore_ascii_case("content-length"))
+        .and_then(|&(_, ref v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let url = Url::parse(&format!("http://fake.streak{}", target))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    Ok(Request {
+        method: method,
+        path: url.path().to_owned(),
+        query: url.query_pairs().into_owned().collect(),
+        headers: headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+pub fn write_response(mut stream: &TcpStream, response: &Response) -> io::Result<()> {
+    write!(stream,
+           "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+           response.status,
+           reason(response.status),
+           response.body.len(),
+           response.body)?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Unknown",
+    }
+}
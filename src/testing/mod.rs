@@ -0,0 +1,435 @@
+//! An in-process fake of the Streak service for integration tests.
+//!
+//! `FakeStreak` runs a small HTTP server on a local port that keeps its
+//! pipelines, stages, boxes, comments and contacts in memory and answers with the same
+//! JSON the real API does. Point a `Client` at it with `FakeStreak::client`,
+//! an `AsyncClient` with `FakeStreak::async_client`, or either with
+//! `ClientBuilder::api_url` and `FakeStreak::api_url`, and the code
+//! under test runs end to end without a network or a Streak account.
+//!
+//! ```rust
+//! extern crate streak;
+//!
+//! use streak::testing::FakeStreak;
+//!
+//! fn main() {
+//!     let streak = FakeStreak::start().expect("to start the fake service");
+//!     let pipeline = streak.add_pipeline("Sales", &["Lead", "Won"]);
+//!     let lead = &pipeline.stage_order[0];
+//!     streak.add_box(&pipeline.pipeline_key, "ACME", lead);
+//!
+//!     let client = streak.client();
+//!     let boxes = streak::api::boxes::list(&client, &pipeline.pipeline_key).expect("list boxes");
+//!     assert_eq!(boxes.len(), 1);
+//!     assert_eq!(boxes[0].name, "ACME");
+//!     assert_eq!(boxes[0].stage_key, *lead);
+//! }
+//! ```
+
+mod http;
+mod routes;
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+
+use api::{BoxKey, ContactKey, PipelineKey, StageKey, Timestamp};
+use api::boxes::{Box, Comment, ContactHandle, User};
+use api::contacts::Contact;
+use api::pipelines::{AclEntry, Color, Field, FieldType, Pipeline, Stage};
+use client::Client;
+use retry::NoRetry;
+#[cfg(feature = "async")]
+use async_client::AsyncClient;
+#[cfg(feature = "async")]
+use tokio_core::reactor::Handle;
+#[cfg(feature = "async")]
+use transport::ReqwestTransport;
+
+/// The API key `FakeStreak::client` authenticates with.
+pub const API_KEY: &'static str = "fake-streak-api-key";
+
+const STAGE_COLORS: [(&'static str, &'static str); 4] = [
+    ("#ffffff", "#3d85c6"),
+    ("#ffffff", "#e69138"),
+    ("#ffffff", "#6aa84f"),
+    ("#ffffff", "#cc0000"),
+];
+
+/// A fake Streak service running on a local port.
+///
+/// The server stops when the `FakeStreak` is dropped.
+#[derive(Debug)]
+pub struct FakeStreak {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Everything the fake service knows about.
+#[derive(Debug, Default)]
+pub struct State {
+    pub pipelines: Vec<Pipeline>,
+    pub boxes: Vec<Box>,
+    pub comments: Vec<Comment>,
+    pub contacts: Vec<Contact>,
+    /// Answer searches for a page past the last one with the last page, as
+    /// some services do, instead of with an empty page.
+    pub repeat_last_search_page: bool,
+    /// How many results of each kind a page of search results holds, 50
+    /// when not set.
+    pub search_page_size: Option<usize>,
+    next_key: u64,
+}
+
+impl State {
+    fn next_key(&mut self, kind: &str) -> String {
+        self.next_key += 1;
+        format!("fake-{}-{}", kind, self.next_key)
+    }
+
+    fn next_number_key(&mut self) -> String {
+        self.next_key += 1;
+        (5000 + self.next_key).to_string()
+    }
+
+    fn create_pipeline(&mut self, name: &str, stage_names: &[&str]) -> Pipeline {
+        let pipeline_key = PipelineKey::new(self.next_key("pipeline"));
+        let now = Timestamp::now();
+
+        let mut stages = HashMap::new();
+        let mut stage_order = Vec::new();
+        for (i, stage_name) in stage_names.iter().enumerate() {
+            let key = StageKey::new(self.next_number_key());
+            stages.insert(key.clone(), stage(&key, stage_name, i));
+            stage_order.push(key);
+        }
+
+        let pipeline = Pipeline {
+            key: pipeline_key.clone(),
+            pipeline_key: pipeline_key,
+            creator_key: user().user_key,
+            name: name.into(),
+            description: None,
+            org_wide: false,
+            fields: Vec::new(),
+            stages: stages,
+            stage_order: stage_order,
+            acl_entries: vec![acl_entry()],
+            owner: Some(acl_entry()),
+            team_key: Some("fake-team".into()),
+            team_wide: Some(false),
+            creation_timestamp: Some(now),
+            last_updated_timestamp: Some(now),
+            last_saved_timestamp: Some(now),
+            box_count_hint: Some(0),
+            box_count: Some(0),
+            sharing_restricted_to_org: Some(false),
+            sharing_restricted_to_team: Some(false),
+            extra: HashMap::new(),
+        };
+        self.pipelines.push(pipeline.clone());
+        pipeline
+    }
+
+    fn create_field(&mut self, pipeline_key: &str, name: &str, field_type: FieldType) -> Option<Field> {
+        let field = Field {
+            name: name.into(),
+            key: self.next_number_key(),
+            field_type: field_type,
+            last_updated_timestamp: Some(Timestamp::now()),
+            dropdown_settings: None,
+            tag_settings: None,
+            formula_settings: None,
+            extra: HashMap::new(),
+        };
+        let pipeline = self.pipeline_mut(pipeline_key)?;
+        pipeline.fields.push(field.clone());
+        Some(field)
+    }
+
+    /// Give every dropdown choice and tag of `field` without a key one.
+    fn assign_item_keys(&mut self, field: &mut Field) {
+        let settings = field.dropdown_settings.iter_mut().chain(field.tag_settings.iter_mut());
+        for item in settings.flat_map(|s| s.items.iter_mut()) {
+            if item.key.is_empty() {
+                item.key = self.next_number_key();
+            }
+        }
+    }
+
+    fn create_box(&mut self, pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> Box {
+        let box_key = BoxKey::new(self.next_key("box"));
+        let b = new_box(&box_key, pipeline_key, name, stage_key);
+        count_box(self, pipeline_key.as_str(), stage_key.as_str(), 1);
+        self.boxes.push(b.clone());
+        b
+    }
+
+    fn pipeline_mut(&mut self, pipeline_key: &str) -> Option<&mut Pipeline> {
+        self.pipelines.iter_mut().find(|p| p.pipeline_key == pipeline_key)
+    }
+
+    fn box_mut(&mut self, box_key: &str) -> Option<&mut Box> {
+        self.boxes.iter_mut().find(|b| b.box_key == box_key)
+    }
+}
+
+impl FakeStreak {
+    /// Start the fake service on a free local port.
+    pub fn start() -> io::Result<FakeStreak> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = state.clone();
+            let shutdown = shutdown.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        if let Err(e) = serve(&stream, &state) {
+                            debug!("Fake Streak failed to answer a request: {}", e);
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(FakeStreak {
+            addr: addr,
+            state: state,
+            shutdown: shutdown,
+            thread: Some(thread),
+        })
+    }
+
+    /// The root of the fake API, to hand to `ClientBuilder::api_url`.
+    pub fn api_url(&self) -> String {
+        format!("http://{}/api", self.addr)
+    }
+
+    /// A client talking to the fake service, which does not retry requests.
+    pub fn client(&self) -> Client {
+        Client::builder(API_KEY)
+            .api_url(&self.api_url())
+            .retry_policy(NoRetry)
+            .build()
+            .expect("to build a client for the fake Streak service")
+    }
+
+    /// An asynchronous client talking to the fake service, which does not
+    /// retry requests.
+    #[cfg(feature = "async")]
+    pub fn async_client(&self, handle: &Handle) -> AsyncClient {
+        Client::builder(API_KEY)
+            .api_url(&self.api_url())
+            .retry_policy(NoRetry)
+            .transport(ReqwestTransport::new())
+            .build_async(handle)
+            .expect("to build an async client for the fake Streak service")
+    }
+
+    /// Lock and inspect or change everything the fake service holds.
+    pub fn state<'a>(&'a self) -> MutexGuard<'a, State> {
+        self.state.lock().expect("fake streak state lock")
+    }
+
+    /// Create a pipeline with a stage for each of `stage_names`, in order.
+    pub fn add_pipeline(&self, name: &str, stage_names: &[&str]) -> Pipeline {
+        self.state().create_pipeline(name, stage_names)
+    }
+
+    /// Add a field without any settings to the pipeline.
+    ///
+    /// Panics when the pipeline does not exist.
+    pub fn add_field(&self, pipeline_key: &PipelineKey, name: &str, field_type: FieldType) -> Field {
+        self.state().create_field(pipeline_key.as_str(), name, field_type).expect("to add a field to an existing pipeline")
+    }
+
+    /// Store `pipeline` as it is, replacing any pipeline with the same key.
+    pub fn insert_pipeline(&self, pipeline: Pipeline) {
+        let mut state = self.state();
+        state.pipelines.retain(|p| p.pipeline_key != pipeline.pipeline_key);
+        state.pipelines.push(pipeline);
+    }
+
+    /// Create a box named `name` in `stage_key` of the pipeline.
+    ///
+    /// Panics when the pipeline does not exist.
+    pub fn add_box(&self, pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> Box {
+        self.state().create_box(pipeline_key, name, stage_key)
+    }
+
+    /// Store `b` as it is, replacing any box with the same key.
+    pub fn insert_box(&self, b: Box) {
+        let mut state = self.state();
+        state.boxes.retain(|existing| existing.box_key != b.box_key);
+        state.boxes.push(b);
+    }
+
+    /// Create a contact and attach it to the box `box_key`.
+    ///
+    /// Panics when the box does not exist.
+    pub fn add_contact(&self, box_key: &BoxKey, given_name: &str, family_name: &str, email: &str) -> Contact {
+        let mut state = self.state();
+        let key = ContactKey::new(state.next_key("contact"));
+        let now = Timestamp::now();
+        let contact = Contact {
+            team_key: "fake-team".into(),
+            given_name: given_name.into(),
+            family_name: family_name.into(),
+            title: None,
+            email_addresses: Some(vec![email.into()]),
+            phone_numbers: None,
+            last_saved_user_key: user().user_key,
+            creator_key: user().user_key,
+            creation_date: now,
+            key: key.clone(),
+            version_timestamp: now,
+            last_saved_timestamp: now,
+            extra: HashMap::new(),
+        };
+
+        {
+            let b = state.box_mut(box_key.as_str()).expect("to add a contact to an existing box");
+            b.contacts.get_or_insert_with(Vec::new).push(ContactHandle {
+                is_starred: false,
+                key: key,
+                extra: HashMap::new(),
+            });
+        }
+        state.contacts.push(contact.clone());
+        contact
+    }
+
+    /// Store `contact` as it is, replacing any contact with the same key.
+    pub fn insert_contact(&self, contact: Contact) {
+        let mut state = self.state();
+        state.contacts.retain(|c| c.key != contact.key);
+        state.contacts.push(contact);
+    }
+}
+
+impl Drop for FakeStreak {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the server up so it notices it should stop.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(stream: &TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let request = http::read_request(stream)?;
+    debug!("Fake Streak received {} {} {}", request.method, request.path, request.body);
+    let response = {
+        let mut state = state.lock().expect("fake streak state lock");
+        routes::route(&mut state, &request)
+    };
+    http::write_response(stream, &response)
+}
+
+fn user() -> User {
+    User {
+        display_name: "Fake".into(),
+        full_name: "Fake User".into(),
+        email: "fake@example.com".into(),
+        image: "https://example.com/fake.png".into(),
+        user_key: "fake-user".into(),
+        extra: HashMap::new(),
+    }
+}
+
+fn acl_entry() -> AclEntry {
+    let user = user();
+    AclEntry {
+        full_name: user.full_name,
+        email: user.email,
+        is_owner: Some(true),
+        image: user.image,
+        display_name: user.display_name,
+        user_key: user.user_key,
+        permission_set_name: "OWNER".into(),
+        extra: HashMap::new(),
+    }
+}
+
+fn stage(key: &StageKey, name: &str, position: usize) -> Stage {
+    let (foreground, background) = STAGE_COLORS[position % STAGE_COLORS.len()];
+    Stage {
+        name: name.into(),
+        key: key.clone(),
+        color: Color {
+            foreground_color: foreground.into(),
+            background_color: background.into(),
+        },
+        box_count: 0,
+        extra: HashMap::new(),
+    }
+}
+
+fn new_box(box_key: &BoxKey, pipeline_key: &PipelineKey, name: &str, stage_key: &StageKey) -> Box {
+    let now = Timestamp::now();
+    Box {
+        last_saved_timestamp: now,
+        pipeline_key: pipeline_key.clone(),
+        creator_key: user().user_key,
+        creation_timestamp: now,
+        last_updated_timestamp: now,
+        last_stage_change_timestamp: now,
+        last_comment_timestamp: None,
+        total_number_of_emails: 0,
+        total_number_of_sent_emails: 0,
+        total_number_of_received_emails: 0,
+        name: name.into(),
+        notes: None,
+        assigned_to_sharing_entries: Vec::new(),
+        creator_sharing_entry: user(),
+        follower_sharing_entries: vec![user()],
+        stage_key: stage_key.clone(),
+        follower_keys: vec![user().user_key],
+        linked_box_keys: Vec::new(),
+        email_addresses_auto_extracted: Vec::new(),
+        email_addresses_blacklist: Vec::new(),
+        email_addresses: Vec::new(),
+        task_complete_count: 0,
+        task_incomplete_count: 0,
+        task_overdue_count: 0,
+        task_total: 0,
+        call_log_count: 0,
+        meeting_notes_count: 0,
+        total_call_log_duration: 0,
+        total_meeting_notes_duration: 0,
+        follower_count: 1,
+        comment_count: 0,
+        gmail_thread_count: 0,
+        file_count: 0,
+        fields: HashMap::new(),
+        box_key: box_key.clone(),
+        key: box_key.clone(),
+        freshness: 1.0,
+        contacts: None,
+        extra: HashMap::new(),
+    }
+}
+
+/// Keep the box counts of a pipeline and one of its stages up to date.
+fn count_box(state: &mut State, pipeline_key: &str, stage_key: &str, change: i32) {
+    let pipeline = state.pipeline_mut(pipeline_key).expect("to add a box to an existing pipeline");
+    pipeline.box_count = Some(pipeline.box_count.unwrap_or(0) + change);
+    pipeline.box_count_hint = Some(pipeline.box_count_hint.unwrap_or(0) + change);
+    if let Some(stage) = pipeline.stages.get_mut(stage_key) {
+        stage.box_count += change;
+    }
+}
@@ -0,0 +1,626 @@
+//! The endpoints the fake service answers, and how.
+
+use std::cmp;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{self, Value};
+
+use api::{BoxKey, CommentKey, Deleted, PipelineKey, StageKey, Timestamp, UserKey};
+use api::boxes::{Comment, User};
+use api::boxes::fields::BoxField;
+use api::pipelines::{Field, FieldItems, FieldType, FormulaSettings};
+use api::search::{BoxHandle, ContactHandle, SearchResponse, SearchResults};
+use client::Status;
+use super::{count_box, stage, user, State};
+use super::http::{Request, Response};
+
+/// How many results of each kind a page of search results holds, unless
+/// `State::search_page_size` says otherwise.
+const SEARCH_PAGE_SIZE: usize = 50;
+
+pub fn route(state: &mut State, req: &Request) -> Response {
+    if req.header("authorization").is_none() {
+        return error(401, "Invalid API key");
+    }
+
+    let path = req.path.trim_start_matches("/api").trim_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["v1", "pipelines"]) => ok(&state.pipelines),
+        ("PUT", ["v1", "pipelines"]) => create_pipeline(state, req),
+        ("GET", ["v1", "pipelines", key]) => {
+            match state.pipelines.iter().find(|p| p.pipeline_key == *key) {
+                Some(pipeline) => ok(pipeline),
+                None => not_found("pipeline", key),
+            }
+        },
+        ("POST", ["v1", "pipelines", key]) => update_pipeline(state, req, key),
+        ("DELETE", ["v1", "pipelines", key]) => {
+            if !state.pipelines.iter().any(|p| p.pipeline_key == *key) {
+                return not_found("pipeline", key);
+            }
+            state.pipelines.retain(|p| p.pipeline_key != *key);
+            state.boxes.retain(|b| b.pipeline_key != *key);
+            ok(&Deleted { success: true })
+        },
+        ("GET", ["v1", "pipelines", key, "stages"]) => {
+            match state.pipelines.iter().find(|p| p.pipeline_key == *key) {
+                Some(pipeline) => ok(&pipeline.stages),
+                None => not_found("pipeline", key),
+            }
+        },
+        ("PUT", ["v1", "pipelines", key, "stages"]) => create_stage(state, req, key),
+        ("GET", ["v1", "pipelines", key, "stages", stage_key]) => {
+            match state.pipelines.iter().find(|p| p.pipeline_key == *key).and_then(|p| p.stages.get(*stage_key)) {
+                Some(stage) => ok(stage),
+                None => not_found("stage", stage_key),
+            }
+        },
+        ("POST", ["v1", "pipelines", key, "stages", stage_key]) => update_stage(state, req, key, stage_key),
+        ("DELETE", ["v1", "pipelines", key, "stages", stage_key]) => delete_stage(state, key, stage_key),
+        ("GET", ["v1", "pipelines", key, "fields"]) => {
+            match state.pipelines.iter().find(|p| p.pipeline_key == *key) {
+                Some(pipeline) => ok(&pipeline.fields),
+                None => not_found("pipeline", key),
+            }
+        },
+        ("PUT", ["v1", "pipelines", key, "fields"]) => create_field(state, req, key),
+        ("GET", ["v1", "pipelines", key, "fields", field_key]) => {
+            match find_field(state, key, field_key) {
+                Some(field) => ok(&field),
+                None => not_found("field", field_key),
+            }
+        },
+        ("POST", ["v1", "pipelines", key, "fields", field_key]) => update_field(state, req, key, field_key),
+        ("DELETE", ["v1", "pipelines", key, "fields", field_key]) => {
+            if find_field(state, key, field_key).is_none() {
+                return not_found("field", field_key);
+            }
+            if let Some(pipeline) = state.pipeline_mut(key) {
+                pipeline.fields.retain(|f| f.key != *field_key);
+            }
+            for b in state.boxes.iter_mut().filter(|b| b.pipeline_key == *key) {
+                b.fields.remove(*field_key);
+            }
+            ok(&Deleted { success: true })
+        },
+        ("GET", ["v1", "pipelines", key, "boxes"]) => {
+            if !state.pipelines.iter().any(|p| p.pipeline_key == *key) {
+                return not_found("pipeline", key);
+            }
+            let limit = req.param("limit").and_then(|l| l.parse().ok()).unwrap_or(usize::max_value());
+            let page: usize = req.param("page").and_then(|p| p.parse().ok()).unwrap_or(0);
+            let boxes: Vec<_> = state.boxes.iter()
+                .filter(|b| b.pipeline_key == *key)
+                .skip(page.saturating_mul(limit))
+                .take(limit)
+                .collect();
+            ok(&boxes)
+        },
+        ("PUT", ["v1", "pipelines", key, "boxes"]) => create_box(state, req, key),
+        ("GET", ["v1", "boxes", key]) => {
+            match state.boxes.iter().find(|b| b.box_key == *key) {
+                Some(b) => ok(b),
+                None => not_found("box", key),
+            }
+        },
+        ("POST", ["v1", "boxes", key]) => update_box(state, req, key),
+        ("GET", ["v1", "boxes", key, "fields", field_key]) => {
+            let b = match state.boxes.iter().find(|b| b.box_key == *key) {
+                Some(b) => b,
+                None => return not_found("box", key),
+            };
+            if find_field(state, b.pipeline_key.as_str(), field_key).is_none() {
+                return not_found("field", field_key);
+            }
+            ok(&BoxField {
+                key: field_key.to_string(),
+                value: b.fields.get(*field_key).cloned(),
+            })
+        },
+        ("POST", ["v1", "boxes", key, "fields", field_key]) => set_box_field(state, req, key, field_key),
+        ("GET", ["v1", "boxes", key, "comments"]) => {
+            if !state.boxes.iter().any(|b| b.box_key == *key) {
+                return not_found("box", key);
+            }
+            let comments: Vec<_> = state.comments.iter().filter(|c| c.box_key == *key).collect();
+            ok(&comments)
+        },
+        ("PUT", ["v1", "boxes", key, "comments"]) => create_comment(state, req, key),
+        ("DELETE", ["v1", "boxes", key, "comments", comment_key]) => delete_comment(state, key, comment_key),
+        ("DELETE", ["v1", "boxes", key]) => {
+            let b = match state.boxes.iter().find(|b| b.box_key == *key) {
+                Some(b) => b.clone(),
+                None => return not_found("box", key),
+            };
+            count_box(state, b.pipeline_key.as_str(), b.stage_key.as_str(), -1);
+            state.boxes.retain(|b| b.box_key != *key);
+            state.comments.retain(|c| c.box_key != *key);
+            for other in &mut state.boxes {
+                other.linked_box_keys.retain(|k| k != key);
+            }
+            ok(&Deleted { success: true })
+        },
+        ("GET", ["v2", "contacts", key]) => {
+            match state.contacts.iter().find(|c| c.key == *key) {
+                Some(contact) => ok(contact),
+                None => not_found("contact", key),
+            }
+        },
+        ("GET", ["v1", "search"]) => search(state, req),
+        _ => error(405, &format!("{} {} is not supported by the fake Streak service", req.method, req.path)),
+    }
+}
+
+fn create_pipeline(state: &mut State, req: &Request) -> Response {
+    let form = req.form();
+    let param = |name: &str| form.iter().find(|&&(ref n, _)| n == name).map(|&(_, ref v)| v.as_str());
+
+    let name = match param("name") {
+        Some(name) if !name.is_empty() => name,
+        _ => return error(400, "name is required"),
+    };
+    let stage_names: Vec<&str> = param("stageNames")
+        .map(|names| names.split(',').map(str::trim).filter(|n| !n.is_empty()).collect())
+        .unwrap_or_default();
+
+    let pipeline_key = state.create_pipeline(name, &stage_names).pipeline_key;
+    let pipeline = state.pipeline_mut(pipeline_key.as_str()).expect("the pipeline just created");
+    pipeline.description = param("description").map(|d| d.to_owned());
+    if let Some(team_key) = param("teamKey") {
+        pipeline.team_key = Some(team_key.into());
+    }
+    pipeline.team_wide = Some(param("teamWide") == Some("true"));
+    ok(pipeline)
+}
+
+fn update_pipeline(state: &mut State, req: &Request, key: &str) -> Response {
+    let changes = match req.json() {
+        Ok(Value::Object(changes)) => changes,
+        _ => return error(400, "The body must be a JSON object"),
+    };
+    let pipeline = match state.pipeline_mut(key) {
+        Some(pipeline) => pipeline,
+        None => return not_found("pipeline", key),
+    };
+
+    for (property, value) in changes {
+        match (property.as_str(), value) {
+            ("name", Value::String(name)) => pipeline.name = name,
+            ("description", Value::String(description)) => pipeline.description = Some(description),
+            ("teamWide", Value::Bool(team_wide)) => pipeline.team_wide = Some(team_wide),
+            ("sharingRestrictedToTeam", Value::Bool(restricted)) => pipeline.sharing_restricted_to_team = Some(restricted),
+            ("stageOrder", order) => {
+                let order: Vec<StageKey> = match serde_json::from_value(order) {
+                    Ok(order) => order,
+                    Err(_) => return error(400, "stageOrder must be a list of stage keys"),
+                };
+                let mut sorted = order.clone();
+                sorted.sort();
+                let mut existing: Vec<_> = pipeline.stages.keys().cloned().collect();
+                existing.sort();
+                if sorted != existing {
+                    return error(400, "stageOrder must hold every stage key exactly once");
+                }
+                pipeline.stage_order = order;
+            },
+            (property, _) => return error(400, &format!("{} can not be set to that value", property)),
+        }
+    }
+    pipeline.last_updated_timestamp = Some(Timestamp::now());
+    ok(pipeline)
+}
+
+fn create_stage(state: &mut State, req: &Request, key: &str) -> Response {
+    let name = match req.form().into_iter().find(|&(ref n, _)| n == "name") {
+        Some((_, name)) => name,
+        None => return error(400, "name is required"),
+    };
+    let stage_key = StageKey::new(state.next_number_key());
+    let pipeline = match state.pipeline_mut(key) {
+        Some(pipeline) => pipeline,
+        None => return not_found("pipeline", key),
+    };
+
+    let stage = stage(&stage_key, &name, pipeline.stage_order.len());
+    pipeline.stages.insert(stage_key.clone(), stage.clone());
+    pipeline.stage_order.push(stage_key);
+    ok(&stage)
+}
+
+fn update_stage(state: &mut State, req: &Request, key: &str, stage_key: &str) -> Response {
+    let changes = match req.json() {
+        Ok(Value::Object(changes)) => changes,
+        _ => return error(400, "The body must be a JSON object"),
+    };
+    let stage = match state.pipeline_mut(key).and_then(|p| p.stages.get_mut(stage_key)) {
+        Some(stage) => stage,
+        None => return not_found("stage", stage_key),
+    };
+
+    for (property, value) in changes {
+        match (property.as_str(), value) {
+            ("name", Value::String(name)) => stage.name = name,
+            ("color", color) => match serde_json::from_value(color) {
+                Ok(color) => stage.color = color,
+                Err(_) => return error(400, "color must have a foregroundColor and a backgroundColor"),
+            },
+            (property, _) => return error(400, &format!("{} can not be set to that value", property)),
+        }
+    }
+    ok(stage)
+}
+
+fn delete_stage(state: &mut State, key: &str, stage_key: &str) -> Response {
+    if state.boxes.iter().any(|b| b.pipeline_key == key && b.stage_key == stage_key) {
+        return error(400, "Only stages without boxes can be deleted");
+    }
+    let pipeline = match state.pipeline_mut(key) {
+        Some(pipeline) => pipeline,
+        None => return not_found("pipeline", key),
+    };
+    if pipeline.stages.remove(stage_key).is_none() {
+        return not_found("stage", stage_key);
+    }
+    pipeline.stage_order.retain(|k| k != stage_key);
+    ok(&Deleted { success: true })
+}
+
+fn create_box(state: &mut State, req: &Request, key: &str) -> Response {
+    let form = req.form();
+    let param = |name: &str| form.iter().find(|&&(ref n, _)| n == name).map(|&(_, ref v)| v.as_str());
+
+    let name = match param("name") {
+        Some(name) if !name.is_empty() => name,
+        _ => return error(400, "name is required"),
+    };
+    let pipeline = match state.pipelines.iter().find(|p| p.pipeline_key == key) {
+        Some(pipeline) => pipeline,
+        None => return not_found("pipeline", key),
+    };
+    let stage_key = match param("stageKey").or_else(|| pipeline.stage_order.first().map(|k| k.as_str())) {
+        Some(stage_key) if pipeline.stages.contains_key(stage_key) => StageKey::new(stage_key),
+        _ => return error(400, "stageKey must be a stage of the pipeline"),
+    };
+    ok(&state.create_box(&PipelineKey::new(key), name, &stage_key))
+}
+
+/// The properties of a box a request sets.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct BoxChanges {
+    name: Option<String>,
+    notes: Option<String>,
+    stage_key: Option<StageKey>,
+    assigned_to_sharing_entries: Option<Vec<Assignee>>,
+    follower_keys: Option<Vec<UserKey>>,
+    linked_box_keys: Option<Vec<BoxKey>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Assignee {
+    email: String,
+}
+
+fn update_box(state: &mut State, req: &Request, key: &str) -> Response {
+    let changes: BoxChanges = match req.json().and_then(serde_json::from_value) {
+        Ok(changes) => changes,
+        Err(e) => return error(400, &format!("Invalid box: {}", e)),
+    };
+    let (pipeline_key, stage_key) = match state.box_mut(key) {
+        Some(b) => (b.pipeline_key.clone(), b.stage_key.clone()),
+        None => return not_found("box", key),
+    };
+    if let Some(ref new_stage) = changes.stage_key {
+        let exists = state.pipelines.iter()
+            .find(|p| p.pipeline_key == pipeline_key)
+            .map_or(false, |p| p.stages.contains_key(new_stage));
+        if !exists {
+            return error(400, "stageKey must be a stage of the pipeline of the box");
+        }
+        if *new_stage != stage_key {
+            count_box(state, pipeline_key.as_str(), stage_key.as_str(), -1);
+            count_box(state, pipeline_key.as_str(), new_stage.as_str(), 1);
+        }
+    }
+    if let Some(ref linked) = changes.linked_box_keys {
+        if let Some(missing) = linked.iter().find(|k| !state.boxes.iter().any(|b| b.box_key == **k)) {
+            return not_found("box", missing.as_str());
+        }
+    }
+
+    let now = Timestamp::now();
+    let b = state.box_mut(key).expect("the box found above");
+    if let Some(name) = changes.name {
+        b.name = name;
+    }
+    if changes.notes.is_some() {
+        b.notes = changes.notes;
+    }
+    if let Some(stage_key) = changes.stage_key {
+        if stage_key != b.stage_key {
+            b.stage_key = stage_key;
+            b.last_stage_change_timestamp = now;
+        }
+    }
+    if let Some(assignees) = changes.assigned_to_sharing_entries {
+        b.assigned_to_sharing_entries = assignees.into_iter()
+            .map(|a| User {
+                display_name: a.email.clone(),
+                full_name: a.email.clone(),
+                user_key: format!("fake-user-{}", a.email).into(),
+                email: a.email,
+                image: user().image,
+                extra: HashMap::new(),
+            })
+            .collect();
+    }
+    if let Some(follower_keys) = changes.follower_keys {
+        b.follower_count = follower_keys.len() as u32;
+        b.follower_keys = follower_keys;
+    }
+    if let Some(linked_box_keys) = changes.linked_box_keys {
+        b.linked_box_keys = linked_box_keys;
+    }
+    b.last_updated_timestamp = now;
+    b.last_saved_timestamp = now;
+    ok(b)
+}
+
+fn create_comment(state: &mut State, req: &Request, key: &str) -> Response {
+    let form = req.form();
+    let message = match form.iter().find(|&&(ref n, _)| n == "message") {
+        Some(&(_, ref message)) if !message.is_empty() => message.clone(),
+        _ => return error(400, "message is required"),
+    };
+    let comment_key = CommentKey::new(state.next_key("comment"));
+    let now = Timestamp::now();
+    let comment = {
+        let b = match state.box_mut(key) {
+            Some(b) => b,
+            None => return not_found("box", key),
+        };
+        b.comment_count += 1;
+        b.last_comment_timestamp = Some(now);
+        let author = user();
+        Comment {
+            comment_key: comment_key,
+            box_key: b.box_key.clone(),
+            pipeline_key: Some(b.pipeline_key.clone()),
+            creator_key: author.user_key.clone(),
+            author: author,
+            timestamp: now,
+            message: message,
+            extra: HashMap::new(),
+        }
+    };
+    state.comments.push(comment.clone());
+    ok(&comment)
+}
+
+fn delete_comment(state: &mut State, key: &str, comment_key: &str) -> Response {
+    if !state.comments.iter().any(|c| c.box_key == key && c.comment_key == comment_key) {
+        return not_found("comment", comment_key);
+    }
+    state.comments.retain(|c| c.comment_key != comment_key);
+    let last = state.comments.iter().filter(|c| c.box_key == key).map(|c| c.timestamp).max();
+    if let Some(b) = state.box_mut(key) {
+        b.comment_count = b.comment_count.saturating_sub(1);
+        b.last_comment_timestamp = last;
+    }
+    ok(&Deleted { success: true })
+}
+
+fn set_box_field(state: &mut State, req: &Request, key: &str, field_key: &str) -> Response {
+    let set: BoxField = match req.json().and_then(|mut body| {
+        body["key"] = Value::String(field_key.into());
+        serde_json::from_value(body)
+    }) {
+        Ok(set) => set,
+        Err(e) => return error(400, &format!("Invalid field value: {}", e)),
+    };
+    let pipeline_key = match state.boxes.iter().find(|b| b.box_key == key) {
+        Some(b) => b.pipeline_key.clone(),
+        None => return not_found("box", key),
+    };
+    if find_field(state, pipeline_key.as_str(), field_key).is_none() {
+        return not_found("field", field_key);
+    }
+
+    let now = Timestamp::now();
+    let b = state.box_mut(key).expect("the box found above");
+    match set.value {
+        Some(ref value) => b.fields.insert(field_key.into(), value.clone()),
+        None => b.fields.remove(field_key),
+    };
+    b.last_updated_timestamp = now;
+    b.last_saved_timestamp = now;
+    ok(&set)
+}
+
+/// The properties of a field a request sets.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct FieldChanges {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    field_type: Option<FieldType>,
+    dropdown_settings: Option<FieldItems>,
+    tag_settings: Option<FieldItems>,
+    formula_settings: Option<FormulaSettings>,
+}
+
+impl FieldChanges {
+    fn apply(self, field: &mut Field) {
+        if let Some(name) = self.name {
+            field.name = name;
+        }
+        if self.dropdown_settings.is_some() {
+            field.dropdown_settings = self.dropdown_settings;
+        }
+        if self.tag_settings.is_some() {
+            field.tag_settings = self.tag_settings;
+        }
+        if self.formula_settings.is_some() {
+            field.formula_settings = self.formula_settings;
+        }
+        field.last_updated_timestamp = Some(Timestamp::now());
+    }
+}
+
+fn field_changes(req: &Request) -> Result<FieldChanges, Response> {
+    req.json()
+        .and_then(serde_json::from_value)
+        .map_err(|e| error(400, &format!("Invalid field: {}", e)))
+}
+
+fn find_field(state: &State, key: &str, field_key: &str) -> Option<Field> {
+    state.pipelines.iter()
+        .find(|p| p.pipeline_key == key)
+        .and_then(|p| p.fields.iter().find(|f| f.key == field_key))
+        .cloned()
+}
+
+fn store_field(state: &mut State, key: &str, mut field: Field) -> Response {
+    state.assign_item_keys(&mut field);
+    if let Some(pipeline) = state.pipeline_mut(key) {
+        if let Some(existing) = pipeline.fields.iter_mut().find(|f| f.key == field.key) {
+            *existing = field.clone();
+        }
+    }
+    ok(&field)
+}
+
+fn create_field(state: &mut State, req: &Request, key: &str) -> Response {
+    let mut changes = match field_changes(req) {
+        Ok(changes) => changes,
+        Err(response) => return response,
+    };
+    let (name, field_type) = match (changes.name.take(), changes.field_type.take()) {
+        (Some(name), Some(field_type)) => (name, field_type),
+        _ => return error(400, "name and type are required"),
+    };
+    match state.create_field(key, &name, field_type) {
+        Some(mut field) => {
+            changes.apply(&mut field);
+            store_field(state, key, field)
+        },
+        None => not_found("pipeline", key),
+    }
+}
+
+fn update_field(state: &mut State, req: &Request, key: &str, field_key: &str) -> Response {
+    let changes = match field_changes(req) {
+        Ok(changes) => changes,
+        Err(response) => return response,
+    };
+    let mut field = match find_field(state, key, field_key) {
+        Some(field) => field,
+        None => return not_found("field", field_key),
+    };
+    if changes.field_type.as_ref().map_or(false, |t| *t != field.field_type) {
+        return error(400, "The type of a field can not be changed");
+    }
+    changes.apply(&mut field);
+    store_field(state, key, field)
+}
+
+/// Boxes whose name contains `query`, or equals `name`, and contacts whose
+/// name or email address contains `query`.
+fn search(state: &State, req: &Request) -> Response {
+    let query = req.param("query").map(|q| q.to_lowercase());
+    let name = req.param("name");
+    if query.is_none() && name.is_none() {
+        return error(400, "Either query or name is required");
+    }
+    let pipeline_keys = req.params("pipelineKey");
+    let stage_keys = req.params("stageKey");
+    let page = req.param("page").and_then(|p| p.parse().ok()).unwrap_or(0);
+
+    let boxes: Vec<_> = state.boxes.iter()
+        .filter(|b| match (&query, name) {
+            (_, Some(name)) => b.name == name,
+            (&Some(ref query), None) => b.name.to_lowercase().contains(query.as_str()),
+            (&None, None) => false,
+        })
+        .filter(|b| pipeline_keys.is_empty() || pipeline_keys.contains(&b.pipeline_key.as_str()))
+        .filter(|b| stage_keys.is_empty() || stage_keys.contains(&b.stage_key.as_str()))
+        .collect();
+    let contacts: Vec<_> = match query {
+        Some(ref query) => state.contacts.iter()
+            .filter(|c| {
+                let full_name = format!("{} {}", c.given_name, c.family_name).to_lowercase();
+                full_name.contains(query.as_str()) || c.email_addresses.iter()
+                    .flat_map(|emails| emails.iter())
+                    .any(|email| email.to_lowercase().contains(query.as_str()))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let page_size = state.search_page_size.unwrap_or(SEARCH_PAGE_SIZE);
+    let page = if state.repeat_last_search_page {
+        let last = cmp::max(boxes.len(), contacts.len()).saturating_sub(1) / page_size;
+        cmp::min(page, last)
+    } else {
+        page
+    };
+    let boxes = boxes.into_iter()
+        .skip(page * page_size)
+        .take(page_size)
+        .map(|b| BoxHandle {
+            box_key: b.box_key.clone(),
+            name: b.name.clone(),
+            last_updated_timestamp: b.last_updated_timestamp,
+            stage_key: b.stage_key.clone(),
+            pipeline_key: b.pipeline_key.clone(),
+            extra: HashMap::new(),
+        })
+        .collect();
+    let contacts = contacts.into_iter()
+        .skip(page * page_size)
+        .take(page_size)
+        .map(|c| ContactHandle {
+            key: c.key.clone(),
+            email_addresses: c.email_addresses.clone(),
+            title: c.title.clone(),
+            extra: HashMap::new(),
+        })
+        .collect();
+
+    ok(&SearchResponse {
+        results: SearchResults {
+            orgs: Some(Vec::new()),
+            boxes: boxes,
+            contacts: Some(contacts),
+            extra: HashMap::new(),
+        },
+        page: page as i32,
+        query: req.param("query").or(name).map(|q| q.to_owned()),
+        extra: HashMap::new(),
+    })
+}
+
+fn ok<T: Serialize>(value: &T) -> Response {
+    match serde_json::to_string(value) {
+        Ok(body) => Response { status: 200, body: body },
+        Err(e) => error(500, &e.to_string()),
+    }
+}
+
+fn not_found(kind: &str, key: &str) -> Response {
+    error(404, &format!("No {} with key {}", kind, key))
+}
+
+fn error(status: u16, message: &str) -> Response {
+    let status = Status {
+        code: Some(status.into()),
+        error: message.into(),
+    };
+    Response {
+        status: status.code.unwrap_or(500) as u16,
+        body: serde_json::to_string(&status).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,267 @@
+//! HTTP transports used by the `Client` to talk to the Streak service.
+//!
+//! The `Client` hands every request to a `Transport`. By default that is a
+//! `ReqwestTransport`, but any implementation can be plugged in with
+//! `Client::with_transport`, for example a `MockTransport` that answers with
+//! canned responses in tests.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::sync::{Arc, Mutex};
+
+use reqwest;
+use serde_json;
+
+pub use reqwest::{StatusCode, Method, Url};
+pub use reqwest::header::Headers;
+
+use client::Status;
+use error::StreakError;
+
+/// A request about to be sent to the Streak service.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: Headers,
+    pub body: Option<String>,
+}
+
+/// A response received from the Streak service.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: Headers,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Create a response with the given status and body, and no headers.
+    pub fn new(status: StatusCode, body: &str) -> HttpResponse {
+        HttpResponse {
+            status: status,
+            headers: Headers::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// A response whose body is read as it arrives, rather than all at once.
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: Headers,
+    pub body: Box<dyn Read>,
+}
+
+impl fmt::Debug for StreamingResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamingResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish()
+    }
+}
+
+impl From<HttpResponse> for StreamingResponse {
+    fn from(res: HttpResponse) -> StreamingResponse {
+        StreamingResponse {
+            status: res.status,
+            headers: res.headers,
+            body: Box::new(Cursor::new(res.body.into_bytes())),
+        }
+    }
+}
+
+/// Sends HTTP requests on behalf of a `Client`.
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Send a single request and return the response, whatever its status.
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, StreakError>;
+
+    /// Send a single request like `send`, but hand back the body unread. The
+    /// default reads the whole response with `send` first.
+    fn send_streaming(&self, request: HttpRequest) -> Result<StreamingResponse, StreakError> {
+        self.send(request).map(StreamingResponse::from)
+    }
+}
+
+/// The default transport, backed by a blocking `reqwest::Client`.
+#[derive(Debug)]
+pub struct ReqwestTransport {
+    reqwest: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Create a transport with a default `reqwest::Client`.
+    pub fn new() -> ReqwestTransport {
+        ReqwestTransport::with_client(reqwest::Client::new())
+    }
+
+    /// Create a transport sending requests through an existing `reqwest::Client`.
+    pub fn with_client(reqwest: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport {
+            reqwest: reqwest,
+        }
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> ReqwestTransport {
+        ReqwestTransport::new()
+    }
+}
+
+impl ReqwestTransport {
+    fn request(&self, request: HttpRequest) -> Result<reqwest::Response, StreakError> {
+        let mut builder = self.reqwest.request(request.method, request.url);
+        builder.headers(request.headers);
+        if let Some(body) = request.body {
+            builder.body(body);
+        }
+        Ok(builder.send()?)
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, StreakError> {
+        let mut res = self.request(request)?;
+
+        let mut body = String::new();
+        res.read_to_string(&mut body)?;
+
+        Ok(HttpResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: body,
+        })
+    }
+
+    fn send_streaming(&self, request: HttpRequest) -> Result<StreamingResponse, StreakError> {
+        let res = self.request(request)?;
+        Ok(StreamingResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: Box::new(res),
+        })
+    }
+}
+
+/// An in-memory transport that answers requests with canned responses.
+///
+/// Responses are matched on the method and the end of the request path, for
+/// example `/v1/pipelines`. Add a query string to the path to match it too.
+/// When several responses are registered for the same request they are
+/// returned in order, and the last one keeps being returned after that.
+/// Requests nothing was registered for get a `404`.
+///
+/// ```rust
+/// extern crate streak;
+///
+/// use streak::Client;
+/// use streak::api::search;
+/// use streak::transport::{Method, MockTransport, StatusCode};
+///
+/// fn main() {
+///     let mock = MockTransport::new();
+///     mock.respond(Method::Get, "/v1/search", StatusCode::ServiceUnavailable, "");
+///     mock.respond(Method::Get, "/v1/search", StatusCode::Ok, r#"{
+///         "results": { "boxes": [] },
+///         "page": 0,
+///         "query": "nothing"
+///     }"#);
+///
+///     let client = Client::with_transport("api-key", mock.clone());
+///     let res = search::query("nothing").send(&client).expect("to retry once and succeed");
+///     assert_eq!(res.results.boxes.len(), 0);
+///     assert_eq!(mock.requests().len(), 2);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    inner: Arc<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    routes: Mutex<Vec<MockRoute>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[derive(Debug)]
+struct MockRoute {
+    method: Method,
+    path: String,
+    responses: VecDeque<HttpResponse>,
+}
+
+impl MockTransport {
+    /// Create a transport without any canned responses.
+    pub fn new() -> MockTransport {
+        MockTransport::default()
+    }
+
+    /// Answer requests for `method` and `path` with `status` and `body`.
+    pub fn respond(&self, method: Method, path: &str, status: StatusCode, body: &str) {
+        self.respond_with(method, path, HttpResponse::new(status, body))
+    }
+
+    /// Answer requests for `method` and `path` with a complete `response`.
+    pub fn respond_with(&self, method: Method, path: &str, response: HttpResponse) {
+        let mut routes = self.inner.routes.lock().expect("mock routes lock");
+        if let Some(route) = routes.iter_mut().find(|r| r.method == method && r.path == path) {
+            route.responses.push_back(response);
+            return;
+        }
+        let mut responses = VecDeque::new();
+        responses.push_back(response);
+        routes.push(MockRoute {
+            method: method,
+            path: path.into(),
+            responses: responses,
+        });
+    }
+
+    /// Every request sent through this transport so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.inner.requests.lock().expect("mock requests lock").clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, StreakError> {
+        let path = request.url.path().to_owned();
+        let path_and_query = match request.url.query() {
+            Some(q) => format!("{}?{}", path, q),
+            None => path.clone(),
+        };
+
+        let response = {
+            let mut routes = self.inner.routes.lock().expect("mock routes lock");
+            let route = routes.iter()
+                .position(|r| r.method == request.method && path_and_query.ends_with(&r.path))
+                .or_else(|| routes.iter().position(|r| r.method == request.method && path.ends_with(&r.path)));
+            route.and_then(|i| {
+                let responses = &mut routes[i].responses;
+                if responses.len() > 1 {
+                    responses.pop_front()
+                } else {
+                    responses.front().cloned()
+                }
+            })
+        };
+
+        let response = match response {
+            Some(response) => response,
+            None => {
+                let status = Status {
+                    code: Some(404),
+                    error: format!("No mock response for {} {}", request.method, path_and_query),
+                };
+                HttpResponse::new(StatusCode::NotFound, &serde_json::to_string(&status)?)
+            },
+        };
+
+        self.inner.requests.lock().expect("mock requests lock").push(request);
+        Ok(response)
+    }
+}